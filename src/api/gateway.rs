@@ -4,12 +4,19 @@
 //! (deezer.com/ajax/gw-light.php), which requires authentication
 //! and provides access to additional endpoints.
 
-use reqwest::{cookie::Jar, Client, Url};
+use reqwest::{cookie::Jar, Client, ClientBuilder, Url};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::sync::Arc;
-use tracing::{error, info, warn};
-
+use std::path::Path;
+use std::sync::{Arc, RwLock as SyncRwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
+
+use crate::cache::{Cache, MemoryCache};
+use crate::converters;
+use crate::crypto;
 use crate::error::{DeezerError, Result};
+use crate::models::TrackAlbum;
 
 /// Gateway API private endpoint.
 const GATEWAY_URL: &str = "https://www.deezer.com/ajax/gw-light.php";
@@ -24,6 +31,25 @@ const MEDIA_URL: &str = "https://media.deezer.com/v1/get_url";
 /// Song server URL template.
 const SONG_SERVER_URL: &str = "https://e-cdns-proxy-{n}.dzcdn.net/mobile/1/{hash}";
 
+/// Number of items requested per page from list methods.
+const SONG_LIST_PAGE_SIZE: usize = 500;
+
+/// Seconds before expiry at which a cached track token is considered stale.
+const TRACK_TOKEN_EXPIRY_MARGIN: u64 = 60;
+
+/// `deezer.getUserData` error keys reported for locked or suspended accounts.
+const ACCOUNT_LOCKED_ERRORS: [&str; 3] = [
+    "USER_ACCOUNT_LOCKED",
+    "ACCOUNT_BLOCKED",
+    "ACCOUNT_SUSPENDED",
+];
+
+/// `deezer.getUserData` error keys asking the user to authenticate again.
+const REAUTH_REQUIRED_ERRORS: [&str; 2] = ["NEED_USER_AUTH_REQUIRED", "TWO_FACTOR_REQUIRED"];
+
+/// How long fetched lyrics are cached.
+const LYRICS_CACHE_TTL: Duration = Duration::from_secs(3600);
+
 /// Default client ID for Deezer API (reserved for future use).
 #[allow(dead_code)]
 const CLIENT_ID: u32 = 172365;
@@ -47,7 +73,7 @@ const CLIENT_SECRET: &str = "fb0bec7ccc063dab0417eb7b0d847f34";
 /// # Example
 ///
 /// ```rust,no_run
-/// use Rusteer::GatewayApi;
+/// use rusteer::GatewayApi;
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -57,12 +83,52 @@ const CLIENT_SECRET: &str = "fb0bec7ccc063dab0417eb7b0d847f34";
 ///     Ok(())
 /// }
 /// ```
-#[derive(Debug)]
 pub struct GatewayApi {
     client: Client,
     arl: String,
+    /// Session tokens, replaced when they expire mid-session.
+    tokens: SyncRwLock<SessionTokens>,
+    /// Held while tokens are refreshed, so concurrent callers refresh once.
+    refresh_lock: tokio::sync::Mutex<()>,
+    /// Cache for song data, track tokens and media URLs.
+    cache: Arc<dyn Cache>,
+}
+
+impl std::fmt::Debug for GatewayApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Tokens grant account access and must not end up in logs. The
+        // cache holds track tokens and signed media URLs, so it is
+        // redacted too.
+        f.debug_struct("GatewayApi")
+            .field("client", &self.client)
+            .field("arl", &"<redacted>")
+            .field("api_token", &"<redacted>")
+            .field("license_token", &"<redacted>")
+            .field("user_id", &self.user_id())
+            .field("cache", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Tokens obtained when authenticating with the ARL.
+#[derive(Debug, Clone)]
+struct SessionTokens {
+    /// CSRF token sent with every gateway call.
     api_token: String,
+    /// Token for requesting media URLs; empty without one.
     license_token: String,
+    /// ID of the logged-in user.
+    user_id: u64,
+}
+
+impl Default for SessionTokens {
+    fn default() -> Self {
+        Self {
+            api_token: "null".to_string(),
+            license_token: String::new(),
+            user_id: 0,
+        }
+    }
 }
 
 /// Song data from the Gateway API.
@@ -78,12 +144,60 @@ pub struct SongData {
     pub media_version: String,
     /// Track token for media URL requests.
     pub track_token: Option<String>,
+    /// Unix timestamp (seconds) at which the track token expires.
+    pub track_token_expire: Option<u64>,
     /// Whether the track is readable/available.
     pub readable: bool,
+    /// Record label, if known.
+    pub label: Option<String>,
     /// Raw JSON data for additional fields.
     pub raw: Value,
 }
 
+impl SongData {
+    /// Build song data from a `song.getData` result.
+    fn from_raw(result: Value) -> Self {
+        let str_field = |key: &str, default: &str| {
+            result
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or(default)
+                .to_string()
+        };
+
+        let md5_origin = str_field("MD5_ORIGIN", "");
+
+        Self {
+            id: str_field("SNG_ID", ""),
+            title: str_field("SNG_TITLE", ""),
+            readable: !md5_origin.is_empty(),
+            md5_origin,
+            label: result
+                .get("LABEL_NAME")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string()),
+            media_version: str_field("MEDIA_VERSION", "1"),
+            track_token: result
+                .get("TRACK_TOKEN")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            track_token_expire: result.get("TRACK_TOKEN_EXPIRE").and_then(|v| {
+                v.as_u64()
+                    .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+            }),
+            raw: result,
+        }
+    }
+
+    /// Get the file size in bytes for a format (e.g. "FLAC", "MP3_320").
+    ///
+    /// Returns `None` if the song is not available in that format.
+    pub fn filesize(&self, format: &str) -> Option<u64> {
+        song_filesize(&self.raw, format)
+    }
+}
+
 /// Lyrics data from the Gateway API.
 #[derive(Debug, Clone)]
 pub struct Lyrics {
@@ -99,6 +213,50 @@ pub struct Lyrics {
     pub raw: Value,
 }
 
+impl Lyrics {
+    /// Build lyrics from a `song.getLyrics` result.
+    fn from_raw(result: Value) -> Self {
+        let synced = result
+            .get("LYRICS_SYNC_JSON")
+            .and_then(|s| s.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|line| {
+                        let text = line.get("line")?.as_str()?;
+                        let timestamp = line
+                            .get("milliseconds")
+                            .and_then(|m| m.as_str())
+                            .and_then(|s| s.parse().ok())
+                            .unwrap_or(0);
+                        Some(SyncedLyric {
+                            line: text.to_string(),
+                            timestamp_ms: timestamp,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Lyrics {
+            id: result
+                .get("LYRICS_ID")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            lyrics_text: result
+                .get("LYRICS_TEXT")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            lyrics_sync: synced,
+            lyrics_copyrights: result
+                .get("LYRICS_COPYRIGHTS")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            raw: result,
+        }
+    }
+}
+
 /// A synced lyric line with timestamp.
 #[derive(Debug, Clone)]
 pub struct SyncedLyric {
@@ -108,8 +266,29 @@ pub struct SyncedLyric {
     pub timestamp_ms: u64,
 }
 
+/// Format synced lyrics as an LRC file.
+///
+/// Each line is prefixed with its `[mm:ss.xx]` timestamp. Returns an empty
+/// string if the lyrics have no synced lines.
+pub fn format_lrc(lyrics: &Lyrics) -> String {
+    lyrics
+        .lyrics_sync
+        .iter()
+        .map(|lyric| {
+            let centis = lyric.timestamp_ms / 10;
+            format!(
+                "[{:02}:{:02}.{:02}]{}\n",
+                centis / 6000,
+                centis / 100 % 60,
+                centis % 100,
+                lyric.line
+            )
+        })
+        .collect()
+}
+
 /// Media URL data.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaUrl {
     /// The actual media URL.
     pub url: String,
@@ -117,6 +296,46 @@ pub struct MediaUrl {
     pub format: String,
     /// Cipher type (e.g., "BF_CBC_STRIPE").
     pub cipher: String,
+    /// AES key (hex encoded), for AES encrypted media.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// AES nonce (hex encoded), for AES encrypted media.
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+impl MediaUrl {
+    /// Check whether the media uses Deezer's stripe scheme.
+    ///
+    /// Stripe media (including unencrypted `NONE` media) can be decrypted
+    /// with the track ID alone; anything else needs [`key`](Self::key) and
+    /// [`nonce`](Self::nonce).
+    pub fn is_stripe(&self) -> bool {
+        matches!(
+            self.cipher.as_str(),
+            crypto::CIPHER_BF_CBC_STRIPE | crypto::CIPHER_NONE
+        )
+    }
+
+    /// Build the parameters to decrypt this media with [`crypto::decrypt_file`]
+    /// or [`crypto::MediaDecryptor`].
+    ///
+    /// Unencrypted `NONE` media gets the "none" type and is passed through.
+    pub fn encryption_params(&self, track_id: &str) -> crypto::EncryptionParams {
+        let encryption_type = match self.cipher.as_str() {
+            crypto::CIPHER_BF_CBC_STRIPE => "blowfish",
+            crypto::CIPHER_NONE => "none",
+            _ => "aes",
+        };
+
+        crypto::EncryptionParams {
+            encryption_type: encryption_type.to_string(),
+            track_id: track_id.to_string(),
+            md5_origin: None,
+            key: self.key.clone(),
+            nonce: self.nonce.clone(),
+        }
+    }
 }
 
 impl GatewayApi {
@@ -129,79 +348,148 @@ impl GatewayApi {
     ///
     /// # Errors
     ///
-    /// Returns `BadCredentials` if the ARL token is invalid.
+    /// Returns `BadCredentials` if the ARL token is invalid, `AccountLocked` if
+    /// the account is locked or suspended, and `ReauthRequired` if Deezer asks
+    /// for the account to be verified again.
     pub async fn new(arl: &str) -> Result<Self> {
-        // Create cookie jar and set ARL
-        let jar = Arc::new(Jar::default());
-        let url = "https://www.deezer.com".parse::<Url>().unwrap();
-        jar.add_cookie_str(&format!("arl={}", arl), &url);
-
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-            .cookie_provider(jar)
+        let client = Self::client_builder(arl)
             .build()
             .map_err(|e| DeezerError::ApiError(format!("Failed to create client: {}", e)))?;
 
-        let mut api = Self {
+        Self::with_client(client, arl).await
+    }
+
+    /// Create a new Gateway API client sending its requests with `client`.
+    ///
+    /// The client must send the ARL cookie to deezer.com and keep the
+    /// session cookies it gets back; build it from
+    /// [`client_builder`](Self::client_builder) to get both. Fetches the
+    /// tokens as [`new`](Self::new) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`new`](Self::new).
+    pub async fn with_client(client: Client, arl: &str) -> Result<Self> {
+        let api = Self {
             client,
             arl: arl.to_string(),
-            api_token: "null".to_string(),
-            license_token: String::new(),
+            tokens: SyncRwLock::new(SessionTokens::default()),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            cache: Arc::new(MemoryCache::new()),
         };
 
-        // Refresh tokens
-        api.refresh_token().await?;
+        // Fetch tokens
+        api.refresh().await?;
 
         Ok(api)
     }
 
-    /// Refresh the API and license tokens.
-    async fn refresh_token(&mut self) -> Result<()> {
-        // First check if we're logged in
-        let user_data = self.get_user_data().await?;
+    /// Get a client builder set up for the gateway.
+    ///
+    /// The builder has a browser user agent and a cookie store holding the
+    /// ARL. Configure it further (timeouts, proxy, ...) and pass the built
+    /// client to [`with_client`](Self::with_client).
+    pub fn client_builder(arl: &str) -> ClientBuilder {
+        let jar = Arc::new(Jar::default());
+        let url = "https://www.deezer.com".parse::<Url>().unwrap();
+        jar.add_cookie_str(&format!("arl={}", arl), &url);
 
-        let user_id = user_data
-            .get("USER")
-            .and_then(|u| u.get("USER_ID"))
-            .and_then(|id| id.as_u64())
-            .unwrap_or(0);
+        Client::builder()
+            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
+            .cookie_provider(jar)
+    }
 
-        if user_id == 0 {
-            return Err(DeezerError::BadCredentials(
-                "ARL token is invalid or expired".to_string(),
-            ));
+    /// Create a new Gateway API client from a Netscape `cookies.txt` export.
+    ///
+    /// The `arl` cookie is read from the file and used as with [`new`](Self::new).
+    ///
+    /// # Errors
+    ///
+    /// Returns `IoError` if the file can't be read and `BadCredentials` if it
+    /// has no `arl` cookie.
+    pub async fn from_cookies_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let arl = arl_from_cookies_file(path.as_ref())?;
+        Self::new(&arl).await
+    }
+
+    /// Fetch new API and license tokens.
+    ///
+    /// Tokens expire after some hours. Gateway calls and media URL requests
+    /// that fail because of an expired token refresh them and retry once on
+    /// their own; call this to refresh them ahead of time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`new`](Self::new) if the ARL can no
+    /// longer be used.
+    pub async fn refresh(&self) -> Result<()> {
+        let _guard = self.refresh_lock.lock().await;
+        self.fetch_tokens().await
+    }
+
+    /// Refresh tokens found expired, unless another caller already did.
+    ///
+    /// `used` are the tokens the failed request was sent with. Callers that
+    /// hit an expired token at the same time wait for the first refresh
+    /// instead of each starting their own.
+    async fn refresh_expired(&self, used: &SessionTokens) -> Result<()> {
+        let _guard = self.refresh_lock.lock().await;
+
+        let current = self.tokens();
+        if current.api_token != used.api_token || current.license_token != used.license_token {
+            debug!("Tokens were already refreshed");
+            return Ok(());
         }
+        self.fetch_tokens().await
+    }
 
-        // Get API token
-        self.api_token = user_data
-            .get("checkForm")
-            .and_then(|t| t.as_str())
-            .unwrap_or("null")
-            .to_string();
-
-        // Get license token
-        self.license_token = user_data
-            .get("USER")
-            .and_then(|u| u.get("OPTIONS"))
-            .and_then(|o| o.get("license_token"))
-            .and_then(|t| t.as_str())
-            .unwrap_or("")
-            .to_string();
+    /// Fetch new tokens and store them. Callers must hold `refresh_lock`.
+    async fn fetch_tokens(&self) -> Result<()> {
+        // First check if we're logged in and the account is usable
+        let response = self.call_api_raw("deezer.getUserData", None).await?;
+        let user_id = check_account_state(&response)?;
+        let user_data = response.get("results").cloned().unwrap_or(Value::Null);
+
+        let tokens = SessionTokens {
+            api_token: user_data
+                .get("checkForm")
+                .and_then(|t| t.as_str())
+                .unwrap_or("null")
+                .to_string(),
+            license_token: user_data
+                .get("USER")
+                .and_then(|u| u.get("OPTIONS"))
+                .and_then(|o| o.get("license_token"))
+                .and_then(|t| t.as_str())
+                .unwrap_or("")
+                .to_string(),
+            user_id,
+        };
 
         info!(
             "Gateway API authenticated. User ID: {}, has license token: {}",
             user_id,
-            !self.license_token.is_empty()
+            !tokens.license_token.is_empty()
         );
 
+        *self.tokens.write().unwrap_or_else(|e| e.into_inner()) = tokens;
         Ok(())
     }
 
-    /// Make a request to the Gateway API.
-    async fn call_api(&self, method: &str, json_data: Option<Value>) -> Result<Value> {
+    /// Get a copy of the current session tokens.
+    fn tokens(&self) -> SessionTokens {
+        self.tokens
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Make a request to the Gateway API and return the full JSON response.
+    async fn call_api_raw(&self, method: &str, json_data: Option<Value>) -> Result<Value> {
+        let api_token = self.tokens().api_token;
         let params = [
             ("api_version", "1.0"),
-            ("api_token", &self.api_token),
+            ("api_token", &api_token),
             ("input", "3"),
             ("method", method),
         ];
@@ -224,12 +512,8 @@ impl GatewayApi {
         let result: Value = match serde_json::from_str(&text) {
             Ok(v) => v,
             Err(e) => {
-                // Log the first 500 chars of response for debugging
-                let preview = if text.len() > 500 {
-                    format!("{}...", &text[..500])
-                } else {
-                    text.clone()
-                };
+                // Log the start of the response for debugging
+                let preview = response_preview(&text, 500);
                 error!(
                     "Failed to parse Gateway response (status {}): {}",
                     status, preview
@@ -241,19 +525,44 @@ impl GatewayApi {
             }
         };
 
-        // Extract results
-        let results = result.get("results").cloned().unwrap_or(Value::Null);
+        Ok(result)
+    }
 
-        if results.is_null() {
-            // Check for errors
-            if let Some(error) = result.get("error") {
-                let error_msg = error.to_string();
-                error!("Gateway API error: {}", error_msg);
-                return Err(DeezerError::ApiError(error_msg));
-            }
+    /// Make a request to the Gateway API.
+    ///
+    /// Returns the `results` of the response. Null results are an
+    /// `ApiError` if the gateway reported an error and `NoDataApi` otherwise.
+    /// An expired API token is refreshed and the call retried once.
+    async fn call_api(&self, method: &str, json_data: Option<Value>) -> Result<Value> {
+        let used = self.tokens();
+        let mut result = self.call_api_raw(method, json_data.clone()).await?;
+
+        if is_invalid_token_error(&result) {
+            warn!("Gateway API token expired, refreshing");
+            self.refresh_expired(&used).await?;
+            result = self.call_api_raw(method, json_data).await?;
         }
 
-        Ok(results)
+        extract_results(method, result)
+    }
+
+    /// Use `cache` for song data and media URLs instead of the client's own
+    /// in-memory cache.
+    ///
+    /// Entries expire with the track token or media URL they contain.
+    pub fn set_cache(&mut self, cache: Arc<dyn Cache>) {
+        self.cache = cache;
+    }
+
+    /// Send further requests with `client`.
+    ///
+    /// The client must carry the ARL cookie, see
+    /// [`client_builder`](Self::client_builder). If it doesn't have the
+    /// current session cookies, the tokens are refreshed on the next call.
+    /// Proxies and timeouts are configured on the builder, or with
+    /// [`Rusteer::set_proxy`](crate::Rusteer::set_proxy).
+    pub fn set_client(&mut self, client: Client) {
+        self.client = client;
     }
 
     /// Get user data (includes checkForm token and license token).
@@ -262,69 +571,169 @@ impl GatewayApi {
     }
 
     /// Get detailed song data.
+    ///
+    /// Served from the cache while its track token is valid.
     pub async fn get_song_data(&self, song_id: &str) -> Result<SongData> {
+        let cache_key = format!("song:{}", song_id);
+        if let Some(cached) = self.cache.get(&cache_key).await {
+            debug!("Using cached song data for song {}", song_id);
+            return Ok(SongData::from_raw(cached));
+        }
+
         let json_data = json!({
             "sng_id": song_id
         });
 
-        let result = self.call_api("song.getData", Some(json_data)).await?;
+        let result = self
+            .call_api("song.getData", Some(json_data))
+            .await
+            .map_err(|e| match e {
+                DeezerError::NoDataApi(_) => DeezerError::TrackNotFound(song_id.to_string()),
+                other => other,
+            })?;
+
+        let song_data = SongData::from_raw(result);
+
+        // The cached song data holds the track token, so it expires with it
+        if song_data.track_token.is_some() {
+            if let Some(ttl) = song_data.track_token_expire.and_then(cache_ttl) {
+                self.cache
+                    .insert(&cache_key, song_data.raw.clone(), Some(ttl))
+                    .await;
+            }
+        }
+
+        Ok(song_data)
+    }
 
-        if result.is_null() {
-            return Err(DeezerError::TrackNotFound(song_id.to_string()));
+    /// Get the track token for a song.
+    ///
+    /// Returns a token stored with [`cache_track_token`](Self::cache_track_token)
+    /// while it is valid, otherwise the token of the song data, which is
+    /// served from the cache until the token expires.
+    pub async fn get_track_token(&self, song_id: &str) -> Result<String> {
+        let cached = self.cache.get(&track_token_key(song_id)).await;
+        if let Some(token) = cached.as_ref().and_then(|t| t.as_str()) {
+            debug!("Using cached track token for song {}", song_id);
+            return Ok(token.to_string());
         }
 
-        Ok(SongData {
-            id: result
-                .get("SNG_ID")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            title: result
-                .get("SNG_TITLE")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            md5_origin: result
-                .get("MD5_ORIGIN")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            media_version: result
-                .get("MEDIA_VERSION")
-                .and_then(|v| v.as_str())
-                .unwrap_or("1")
-                .to_string(),
-            track_token: result
-                .get("TRACK_TOKEN")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-            readable: !result
-                .get("MD5_ORIGIN")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .is_empty(),
-            raw: result,
-        })
+        self.get_song_data(song_id)
+            .await?
+            .track_token
+            .ok_or_else(|| DeezerError::NoDataApi("No track token".to_string()))
+    }
+
+    /// Store a track token obtained elsewhere (e.g. from cached song data).
+    ///
+    /// The token is stored in the client's [`Cache`] until it expires.
+    /// Tokens without a known expiry, or about to expire, are not cached.
+    pub async fn cache_track_token(&self, song_id: &str, token: &str, expire: Option<u64>) {
+        let Some(ttl) = expire.and_then(cache_ttl) else {
+            return;
+        };
+
+        self.cache
+            .insert(&track_token_key(song_id), json!(token), Some(ttl))
+            .await;
+    }
+
+    /// Forget the cached track token of a song, along with its song data.
+    pub async fn forget_track_token(&self, song_id: &str) {
+        self.cache.remove(&track_token_key(song_id)).await;
+        self.cache.remove(&format!("song:{}", song_id)).await;
     }
 
     /// Get album data (list of songs).
     pub async fn get_album_data(&self, album_id: &str) -> Result<Value> {
         let json_data = json!({
-            "alb_id": album_id,
-            "nb": -1
+            "alb_id": album_id
         });
 
-        self.call_api("song.getListByAlbum", Some(json_data)).await
+        self.call_api_paginated("song.getListByAlbum", json_data)
+            .await
+    }
+
+    /// Get the tracks of an album as models.
+    ///
+    /// Parsed from the same song list as [`get_album_data`](Self::get_album_data),
+    /// which can include tracks the public API hides due to region
+    /// restrictions.
+    pub async fn get_album_tracks(&self, album_id: &str) -> Result<Vec<TrackAlbum>> {
+        let data = self.get_album_data(album_id).await?;
+        Ok(converters::parse_gateway_album_tracks(&data))
     }
 
     /// Get playlist data (list of songs).
     pub async fn get_playlist_data(&self, playlist_id: &str) -> Result<Value> {
         let json_data = json!({
-            "playlist_id": playlist_id,
-            "nb": -1
+            "playlist_id": playlist_id
         });
 
-        self.call_api("playlist.getSongs", Some(json_data)).await
+        self.call_api_paginated("playlist.getSongs", json_data)
+            .await
+    }
+
+    /// Get the total download size of an album in a format (e.g. "FLAC").
+    ///
+    /// Uses the `FILESIZE_*` fields of the album song list, so the whole
+    /// album needs a single gateway call per page rather than one call per
+    /// track. Songs not available in `format` don't count towards the total.
+    pub async fn album_download_sizes(&self, album_id: &str, format: &str) -> Result<u64> {
+        let album = self.get_album_data(album_id).await?;
+
+        let total = album
+            .get("data")
+            .and_then(|d| d.as_array())
+            .map(|songs| {
+                songs
+                    .iter()
+                    .filter_map(|song| song_filesize(song, format))
+                    .sum()
+            })
+            .unwrap_or(0);
+
+        Ok(total)
+    }
+
+    /// Call a song list method page by page until every song is fetched.
+    ///
+    /// Returns the same shape as a single call (`data`, `count`, `total`),
+    /// with `data` holding the songs of all pages.
+    async fn call_api_paginated(&self, method: &str, json_data: Value) -> Result<Value> {
+        let mut songs = Vec::new();
+        let mut total = None;
+
+        loop {
+            let mut page_data = json_data.clone();
+            page_data["start"] = json!(songs.len());
+            page_data["nb"] = json!(SONG_LIST_PAGE_SIZE);
+
+            let page = self.call_api(method, Some(page_data)).await?;
+
+            if total.is_none() {
+                total = page.get("total").and_then(|t| t.as_u64());
+            }
+
+            let data = page
+                .get("data")
+                .and_then(|d| d.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let fetched = data.len();
+            songs.extend(data);
+
+            if is_last_page(fetched, songs.len(), total) {
+                break;
+            }
+        }
+
+        let count = songs.len();
+        Ok(json!({
+            "data": songs,
+            "count": count,
+            "total": total.unwrap_or(count as u64)
+        }))
     }
 
     /// Get lyrics for a song.
@@ -333,53 +742,25 @@ impl GatewayApi {
             "sng_id": song_id
         });
 
-        let result = self.call_api("song.getLyrics", Some(json_data)).await?;
-
-        if result.is_null() {
-            return Err(DeezerError::NoDataApi(format!(
-                "No lyrics for song {}",
-                song_id
-            )));
+        let cache_key = format!("lyrics:{}", song_id);
+        if let Some(cached) = self.cache.get(&cache_key).await {
+            return Ok(Lyrics::from_raw(cached));
         }
 
-        let synced = result
-            .get("LYRICS_SYNC_JSON")
-            .and_then(|s| s.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|line| {
-                        let text = line.get("line")?.as_str()?;
-                        let timestamp = line
-                            .get("milliseconds")
-                            .and_then(|m| m.as_str())
-                            .and_then(|s| s.parse().ok())
-                            .unwrap_or(0);
-                        Some(SyncedLyric {
-                            line: text.to_string(),
-                            timestamp_ms: timestamp,
-                        })
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+        let result = self
+            .call_api("song.getLyrics", Some(json_data))
+            .await
+            .map_err(|e| match e {
+                DeezerError::NoDataApi(_) => {
+                    DeezerError::NoDataApi(format!("No lyrics for song {}", song_id))
+                }
+                other => other,
+            })?;
 
-        Ok(Lyrics {
-            id: result
-                .get("LYRICS_ID")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            lyrics_text: result
-                .get("LYRICS_TEXT")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-            lyrics_sync: synced,
-            lyrics_copyrights: result
-                .get("LYRICS_COPYRIGHTS")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-            raw: result,
-        })
+        self.cache
+            .insert(&cache_key, result.clone(), Some(LYRICS_CACHE_TTL))
+            .await;
+        Ok(Lyrics::from_raw(result))
     }
 
     /// Get page track data (additional track metadata).
@@ -391,6 +772,74 @@ impl GatewayApi {
         self.call_api("deezer.pageTrack", Some(json_data)).await
     }
 
+    /// Get the waveform of a song, as shown by Deezer's player.
+    ///
+    /// Each value is the intensity of one slice of the song. Only some
+    /// songs have one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoDataApi` if the track page has no waveform.
+    pub async fn get_track_waveform(&self, song_id: &str) -> Result<Vec<f32>> {
+        let page = self.get_page_track(song_id).await?;
+
+        extract_waveform(&page)
+            .ok_or_else(|| DeezerError::NoDataApi(format!("No waveform for song {}", song_id)))
+    }
+
+    /// Get the playlists the user follows but didn't create.
+    ///
+    /// Returns the raw gateway playlist objects (`PLAYLIST_ID`, `TITLE`,
+    /// `PARENT_USERNAME`, ...) of the profile's playlists tab.
+    pub async fn get_followed_playlists(&self) -> Result<Vec<Value>> {
+        let mut playlists = Vec::new();
+        let mut total = None;
+
+        loop {
+            let json_data = json!({
+                "user_id": self.user_id(),
+                "tab": "playlists",
+                "start": playlists.len(),
+                "nb": SONG_LIST_PAGE_SIZE
+            });
+
+            let page = self.call_api("deezer.pageProfile", Some(json_data)).await?;
+            let tab = &page["TAB"]["playlists"];
+
+            if total.is_none() {
+                total = tab.get("total").and_then(|t| t.as_u64());
+            }
+
+            let data = tab
+                .get("data")
+                .and_then(|d| d.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let fetched = data.len();
+            playlists.extend(data);
+
+            if is_last_page(fetched, playlists.len(), total) {
+                break;
+            }
+        }
+
+        // The tab mixes in the user's own playlists
+        let user_id = self.user_id();
+        playlists.retain(|playlist| is_followed_playlist(playlist, user_id));
+
+        Ok(playlists)
+    }
+
+    /// Get page artist data (biography, fan count and top content).
+    pub async fn get_page_artist(&self, artist_id: &str) -> Result<Value> {
+        let json_data = json!({
+            "art_id": artist_id,
+            "lang": "en"
+        });
+
+        self.call_api("deezer.pageArtist", Some(json_data)).await
+    }
+
     /// Get episode data (for podcasts).
     pub async fn get_episode_data(&self, episode_id: &str) -> Result<Value> {
         let json_data = json!({
@@ -430,25 +879,63 @@ impl GatewayApi {
         track_tokens: &[String],
         quality: &str,
     ) -> Result<Vec<MediaUrl>> {
-        let json_data = json!({
-            "license_token": self.license_token,
-            "media": [
-                {
-                    "type": "FULL",
-                    "formats": [
-                        {
-                            "cipher": "BF_CBC_STRIPE",
-                            "format": quality
-                        }
-                    ]
-                }
-            ],
-            "track_tokens": track_tokens
-        });
+        self.get_media_url_with_cipher(track_tokens, quality, crypto::CIPHER_BF_CBC_STRIPE)
+            .await
+    }
+
+    /// Get media URLs for tracks, requesting a specific cipher.
+    ///
+    /// The cipher of each returned [`MediaUrl`] is the one reported by
+    /// Deezer, falling back to the requested cipher if it is missing.
+    /// URLs are served from the cache until they expire.
+    pub async fn get_media_url_with_cipher(
+        &self,
+        track_tokens: &[String],
+        quality: &str,
+        cipher: &str,
+    ) -> Result<Vec<MediaUrl>> {
+        let cache_key = media_cache_key(track_tokens, quality, cipher);
+        if let Some(urls) = self
+            .cache
+            .get(&cache_key)
+            .await
+            .and_then(|cached| serde_json::from_value::<Vec<MediaUrl>>(cached).ok())
+        {
+            debug!("Using cached media URLs for {}", cache_key);
+            return Ok(urls);
+        }
 
-        let response = self.client.post(MEDIA_URL).json(&json_data).send().await?;
+        let mut refreshed = false;
+        let result = loop {
+            let used = self.tokens();
+            let json_data = json!({
+                "license_token": used.license_token,
+                "media": [
+                    {
+                        "type": "FULL",
+                        "formats": [
+                            {
+                                "cipher": cipher,
+                                "format": quality
+                            }
+                        ]
+                    }
+                ],
+                "track_tokens": track_tokens
+            });
+
+            let response = self.client.post(MEDIA_URL).json(&json_data).send().await?;
+            let result: Value = response.json().await?;
+
+            if !refreshed && is_expired_license_error(&result) {
+                warn!("License token expired, refreshing");
+                self.refresh_expired(&used).await?;
+                refreshed = true;
+                continue;
+            }
 
-        let result: Value = response.json().await?;
+            break result;
+        };
 
         // Check for errors
         if let Some(errors) = result.get("errors").and_then(|e| e.as_array()) {
@@ -467,27 +954,19 @@ impl GatewayApi {
             .ok_or_else(|| DeezerError::NoDataApi("No media data".to_string()))?;
 
         let mut urls = Vec::new();
+        let mut expires_at: Option<u64> = None;
 
         for item in media_data {
             if let Some(media_arr) = item.get("media").and_then(|m| m.as_array()) {
                 for media in media_arr {
+                    if let Some(exp) = media.get("exp").and_then(|e| e.as_u64()) {
+                        expires_at = Some(expires_at.map_or(exp, |current| current.min(exp)));
+                    }
+
                     if let Some(sources) = media.get("sources").and_then(|s| s.as_array()) {
                         for source in sources {
                             if let Some(url) = source.get("url").and_then(|u| u.as_str()) {
-                                urls.push(MediaUrl {
-                                    url: url.to_string(),
-                                    format: media
-                                        .get("format")
-                                        .and_then(|f| f.as_str())
-                                        .unwrap_or("")
-                                        .to_string(),
-                                    cipher: media
-                                        .get("cipher")
-                                        .and_then(|c| c.get("type"))
-                                        .and_then(|t| t.as_str())
-                                        .unwrap_or("BF_CBC_STRIPE")
-                                        .to_string(),
-                                });
+                                urls.push(media_url_from(media, url, cipher));
                             }
                         }
                     }
@@ -495,9 +974,23 @@ impl GatewayApi {
             }
         }
 
+        // URLs without a known expiry are not cached
+        if let Some(ttl) = expires_at.and_then(cache_ttl) {
+            if let Ok(value) = serde_json::to_value(&urls) {
+                self.cache.insert(&cache_key, value, Some(ttl)).await;
+            }
+        }
+
         Ok(urls)
     }
 
+    /// Drop cached media URLs so the next request fetches fresh ones.
+    pub async fn forget_media_urls(&self, track_tokens: &[String], quality: &str, cipher: &str) {
+        self.cache
+            .remove(&media_cache_key(track_tokens, quality, cipher))
+            .await;
+    }
+
     /// Generate a legacy song URL.
     ///
     /// # Arguments
@@ -511,21 +1004,18 @@ impl GatewayApi {
     }
 
     /// Check if a song URL is accessible.
+    ///
+    /// Only the first byte is requested, so the file isn't downloaded; the
+    /// URL exists if the server answers with a success status.
     pub async fn song_exists(&self, song_url: &str) -> Result<bool> {
         // Special handling for Spreaker URLs
         if song_url.contains("spreaker.com") {
-            let response = self.client.get(song_url).send().await?;
+            let response = self.probe_url(song_url).await?;
             return Ok(response.status().is_success());
         }
 
-        match self.client.get(song_url).send().await {
-            Ok(response) => {
-                let bytes = response.bytes().await?;
-                if bytes.is_empty() {
-                    return Err(DeezerError::TrackNotFound(song_url.to_string()));
-                }
-                Ok(true)
-            }
+        match self.probe_url(song_url).await {
+            Ok(response) => Ok(response.status().is_success()),
             Err(e) => {
                 warn!("Failed to check song URL {}: {}", song_url, e);
 
@@ -537,11 +1027,9 @@ impl GatewayApi {
                             &format!("e-cdns-proxy-{}", (i + 1) % 8),
                             1,
                         );
-                        if let Ok(response) = self.client.get(&fallback_url).send().await {
-                            if let Ok(bytes) = response.bytes().await {
-                                if !bytes.is_empty() {
-                                    return Ok(true);
-                                }
+                        if let Ok(response) = self.probe_url(&fallback_url).await {
+                            if response.status().is_success() {
+                                return Ok(true);
                             }
                         }
                     }
@@ -552,6 +1040,32 @@ impl GatewayApi {
         }
     }
 
+    /// Request the first byte of `url`, leaving the body unread.
+    async fn probe_url(&self, url: &str) -> reqwest::Result<reqwest::Response> {
+        self.client
+            .get(url)
+            .header(reqwest::header::RANGE, "bytes=0-0")
+            .send()
+            .await
+    }
+
+    /// Get a song's media URL on the legacy CDN, if it is still served there.
+    ///
+    /// The URL is built from the song's `MD5_ORIGIN` and `MEDIA_VERSION` and
+    /// checked with [`song_exists`](Self::song_exists). Some older content
+    /// is only available this way once the media endpoint refuses it.
+    pub async fn get_legacy_media_url(&self, song: &SongData) -> Result<Option<MediaUrl>> {
+        let Some(media_url) = legacy_media_url(song) else {
+            return Ok(None);
+        };
+
+        if self.song_exists(&media_url.url).await? {
+            Ok(Some(media_url))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Check if the client is authenticated.
     pub async fn is_logged_in(&self) -> bool {
         match self.get_user_data().await {
@@ -571,16 +1085,596 @@ impl GatewayApi {
         &self.arl
     }
 
+    /// Get the license token used to request media URLs.
+    ///
+    /// Empty when the account has no license token. Treat it like the ARL:
+    /// it grants access to the account's streams. It changes when the
+    /// session is [refreshed](Self::refresh), which is why this returns a
+    /// copy rather than borrowing from the client.
+    pub fn license_token(&self) -> String {
+        self.tokens().license_token
+    }
+
     /// Check if we have a license token (premium access).
     pub fn has_license_token(&self) -> bool {
-        !self.license_token.is_empty()
+        !self.tokens().license_token.is_empty()
+    }
+
+    /// Get the ID of the logged-in user.
+    pub fn user_id(&self) -> u64 {
+        self.tokens().user_id
+    }
+}
+
+/// Inspect a `deezer.getUserData` response and return the user ID.
+///
+/// Distinguishes an invalid ARL from accounts that are locked or that
+/// need re-verification, which Deezer reports through the `error` object.
+fn check_account_state(response: &Value) -> Result<u64> {
+    if let Some(error) = response.get("error").and_then(|e| e.as_object()) {
+        for (key, value) in error {
+            let message = value.as_str().unwrap_or(key).to_string();
+
+            if ACCOUNT_LOCKED_ERRORS.contains(&key.as_str()) {
+                return Err(DeezerError::AccountLocked(message));
+            }
+
+            if REAUTH_REQUIRED_ERRORS.contains(&key.as_str()) {
+                return Err(DeezerError::ReauthRequired(message));
+            }
+        }
+    }
+
+    let user_id = response
+        .get("results")
+        .and_then(|r| r.get("USER"))
+        .and_then(|u| u.get("USER_ID"))
+        .and_then(|id| id.as_u64())
+        .unwrap_or(0);
+
+    if user_id == 0 {
+        return Err(DeezerError::BadCredentials(
+            "ARL token is invalid or expired".to_string(),
+        ));
+    }
+
+    Ok(user_id)
+}
+
+/// Read the `arl` cookie from a Netscape `cookies.txt` file.
+pub(crate) fn arl_from_cookies_file(path: &Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path)?;
+
+    arl_from_cookies(&contents).ok_or_else(|| {
+        DeezerError::BadCredentials(format!("No arl cookie found in {}", path.display()))
+    })
+}
+
+/// Find the `arl` cookie in Netscape `cookies.txt` contents.
+///
+/// Lines are tab-separated: domain, subdomain flag, path, secure flag,
+/// expiry, name and value. Comments start with `#`, except for the
+/// `#HttpOnly_` prefix browsers put on HTTP-only cookies.
+fn arl_from_cookies(contents: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let line = line.trim_end_matches('\r');
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.starts_with('#') {
+            return None;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 || !fields[0].ends_with("deezer.com") || fields[5] != "arl" {
+            return None;
+        }
+
+        let value = fields[6].trim();
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Truncate a response body to at most `max_bytes` for logging.
+///
+/// Cuts on a char boundary so multi-byte characters don't cause a panic.
+fn response_preview(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+
+    let end = (0..=max_bytes)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0);
+    format!("{}...", &text[..end])
+}
+
+/// Extract the `results` of a gateway response.
+///
+/// The gateway always sends an `error` field, which is an empty array or
+/// object on success, so only a non-empty one counts as an error.
+fn extract_results(method: &str, response: Value) -> Result<Value> {
+    let results = response.get("results").cloned().unwrap_or(Value::Null);
+    if !results.is_null() {
+        return Ok(results);
+    }
+
+    let has_error = match response.get("error") {
+        None | Some(Value::Null) => false,
+        Some(Value::Array(errors)) => !errors.is_empty(),
+        Some(Value::Object(errors)) => !errors.is_empty(),
+        Some(Value::String(error)) => !error.is_empty(),
+        Some(_) => true,
+    };
+
+    if has_error {
+        let error_msg = response["error"].to_string();
+        error!("Gateway API error: {}", error_msg);
+        return Err(DeezerError::ApiError(error_msg));
+    }
+
+    Err(DeezerError::NoDataApi(format!(
+        "{} returned no results",
+        method
+    )))
+}
+
+/// Check whether a gateway response was rejected for an expired API token.
+fn is_invalid_token_error(response: &Value) -> bool {
+    let Some(errors) = response.get("error").and_then(|e| e.as_object()) else {
+        return false;
+    };
+
+    errors.contains_key("VALID_TOKEN_REQUIRED")
+        || errors.values().any(|message| {
+            message
+                .as_str()
+                .is_some_and(|m| m.to_ascii_lowercase().contains("invalid api token"))
+        })
+}
+
+/// Check whether a media URL response was rejected for an expired license token.
+///
+/// Missing rights and bad track tokens are reported the same way, so only
+/// messages about the license token being invalid or expired count.
+fn is_expired_license_error(response: &Value) -> bool {
+    let Some(errors) = response.get("errors").and_then(|e| e.as_array()) else {
+        return false;
+    };
+
+    errors.iter().any(|error| {
+        let message = error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        message.contains("license") && (message.contains("invalid") || message.contains("expired"))
+    })
+}
+
+/// Read the waveform of a `deezer.pageTrack` response.
+///
+/// It's sent either at the top level or in the `DATA` song object, with
+/// values as numbers or numeric strings.
+fn extract_waveform(page: &Value) -> Option<Vec<f32>> {
+    let values = page
+        .get("WAVEFORM")
+        .or_else(|| page["DATA"].get("WAVEFORM"))?
+        .as_array()?;
+
+    let waveform: Vec<f32> = values
+        .iter()
+        .map(|v| {
+            v.as_f64()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+                .map(|v| v as f32)
+        })
+        .collect::<Option<_>>()?;
+
+    (!waveform.is_empty()).then_some(waveform)
+}
+
+/// Read the `FILESIZE_<format>` field of a gateway song object.
+///
+/// The gateway sends sizes as strings or numbers; zero means unavailable.
+fn song_filesize(song: &Value, format: &str) -> Option<u64> {
+    let value = song.get(format!("FILESIZE_{}", format))?;
+    let size = value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))?;
+
+    (size > 0).then_some(size)
+}
+
+/// Check whether a gateway playlist object belongs to someone other than `user_id`.
+fn is_followed_playlist(playlist: &Value, user_id: u64) -> bool {
+    let owner = match playlist.get("PARENT_USER_ID") {
+        Some(Value::String(id)) => id.parse::<u64>().ok(),
+        Some(id) => id.as_u64(),
+        None => None,
+    };
+
+    owner.is_some_and(|owner| owner != user_id)
+}
+
+/// Check whether a song list page was the last one.
+///
+/// Relies on the reported total when there is one, since the gateway may
+/// return fewer songs per page than requested.
+fn is_last_page(fetched: usize, collected: usize, total: Option<u64>) -> bool {
+    if fetched == 0 {
+        return true;
     }
+
+    match total {
+        Some(total) => collected as u64 >= total,
+        None => fetched < SONG_LIST_PAGE_SIZE,
+    }
+}
+
+/// Build a [`MediaUrl`] for one source of a media entry.
+///
+/// The cipher falls back to `requested_cipher` if Deezer doesn't report
+/// one. The AES key and nonce are read from the cipher object.
+fn media_url_from(media: &Value, url: &str, requested_cipher: &str) -> MediaUrl {
+    let cipher = media.get("cipher");
+    let cipher_field = |name: &str| {
+        cipher
+            .and_then(|c| c.get(name))
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    MediaUrl {
+        url: url.to_string(),
+        format: media
+            .get("format")
+            .and_then(|f| f.as_str())
+            .unwrap_or("")
+            .to_string(),
+        cipher: cipher_field("type").unwrap_or_else(|| requested_cipher.to_string()),
+        key: cipher_field("key"),
+        nonce: cipher_field("nonce"),
+    }
+}
+
+/// Build the cache key for the media URLs of a request.
+fn media_cache_key(track_tokens: &[String], quality: &str, cipher: &str) -> String {
+    format!("media:{}:{}:{}", quality, cipher, track_tokens.join(","))
+}
+
+/// Get the cache key of a song's track token.
+fn track_token_key(song_id: &str) -> String {
+    format!("track_token:{}", song_id)
+}
+
+/// Get how long an entry expiring at `expires_at` (Unix seconds) may be cached.
+///
+/// Leaves the same safety margin as cached track tokens; returns `None` if
+/// the entry is already (nearly) expired.
+fn cache_ttl(expires_at: u64) -> Option<Duration> {
+    expires_at
+        .checked_sub(unix_now() + TRACK_TOKEN_EXPIRY_MARGIN)
+        .filter(|&secs| secs > 0)
+        .map(Duration::from_secs)
+}
+
+/// Build the legacy CDN media URL of a song, without checking it.
+///
+/// Legacy media is MP3 128 encrypted with the Blowfish stripe cipher. The
+/// proxy server is picked from the first character of `MD5_ORIGIN`.
+fn legacy_media_url(song: &SongData) -> Option<MediaUrl> {
+    let server = song.md5_origin.chars().next()?.to_digit(16)? % 8;
+    let hash = crypto::gen_song_hash(&song.id, &song.md5_origin, &song.media_version);
+
+    Some(MediaUrl {
+        url: GatewayApi::get_song_url(server as u8, &hash),
+        format: "MP3_128".to_string(),
+        cipher: crypto::CIPHER_BF_CBC_STRIPE.to_string(),
+        key: None,
+        nonce: None,
+    })
+}
+
+/// Current Unix time in seconds.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Build a client without authenticating, for tests that stay offline.
+    fn offline_api() -> GatewayApi {
+        GatewayApi {
+            client: Client::new(),
+            arl: String::new(),
+            tokens: SyncRwLock::new(SessionTokens::default()),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            cache: Arc::new(MemoryCache::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_track_token() {
+        let api = offline_api();
+        api.cache_track_token("1", "token", Some(unix_now() + 3600))
+            .await;
+        assert_eq!(api.get_track_token("1").await.unwrap(), "token");
+
+        // Tokens without expiry, or about to expire, are not stored
+        api.cache_track_token("2", "token", None).await;
+        api.cache_track_token("3", "token", Some(unix_now())).await;
+        assert!(api.cache.get(&track_token_key("2")).await.is_none());
+        assert!(api.cache.get(&track_token_key("3")).await.is_none());
+
+        api.forget_track_token("1").await;
+        assert!(api.cache.get(&track_token_key("1")).await.is_none());
+    }
+
+    #[test]
+    fn test_song_data_from_raw() {
+        let song = SongData::from_raw(json!({
+            "SNG_ID": "3135556",
+            "MD5_ORIGIN": "abc",
+            "TRACK_TOKEN": "token",
+            "TRACK_TOKEN_EXPIRE": "1700000000"
+        }));
+        assert_eq!(song.id, "3135556");
+        assert!(song.readable);
+        assert_eq!(song.media_version, "1");
+        assert_eq!(song.track_token_expire, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn test_legacy_media_url() {
+        let song = SongData::from_raw(json!({
+            "SNG_ID": "3135556",
+            "MD5_ORIGIN": "c94f6dfa1d6e8f2b31d5ad1c26e4fa5e",
+            "MEDIA_VERSION": "4"
+        }));
+        let media_url = legacy_media_url(&song).unwrap();
+        let hash = crypto::gen_song_hash("3135556", &song.md5_origin, "4");
+        assert_eq!(
+            media_url.url,
+            format!("https://e-cdns-proxy-4.dzcdn.net/mobile/1/{}", hash)
+        );
+        assert!(media_url.is_stripe());
+        assert_eq!(media_url.format, "MP3_128");
+
+        let unreadable = SongData::from_raw(json!({"SNG_ID": "3135556"}));
+        assert!(legacy_media_url(&unreadable).is_none());
+    }
+
+    #[test]
+    fn test_format_lrc() {
+        let lyrics = Lyrics {
+            id: "1".to_string(),
+            lyrics_text: None,
+            lyrics_sync: vec![
+                SyncedLyric {
+                    line: "First".to_string(),
+                    timestamp_ms: 12_345,
+                },
+                SyncedLyric {
+                    line: "Second".to_string(),
+                    timestamp_ms: 754_001,
+                },
+            ],
+            lyrics_copyrights: None,
+            raw: Value::Null,
+        };
+        assert_eq!(format_lrc(&lyrics), "[00:12.34]First\n[12:34.00]Second\n");
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        assert_eq!(cache_ttl(0), None);
+        assert_eq!(cache_ttl(unix_now() + TRACK_TOKEN_EXPIRY_MARGIN), None);
+
+        let ttl = cache_ttl(unix_now() + TRACK_TOKEN_EXPIRY_MARGIN + 3600).unwrap();
+        assert!(ttl.as_secs() > 3500 && ttl.as_secs() <= 3600);
+    }
+
+    #[test]
+    fn test_check_account_state() {
+        let ok = json!({"error": [], "results": {"USER": {"USER_ID": 42}}});
+        assert_eq!(check_account_state(&ok).unwrap(), 42);
+
+        let invalid = json!({"error": [], "results": {"USER": {"USER_ID": 0}}});
+        assert!(matches!(
+            check_account_state(&invalid),
+            Err(DeezerError::BadCredentials(_))
+        ));
+
+        for key in ACCOUNT_LOCKED_ERRORS {
+            let locked = json!({"error": {key: "Account locked"}, "results": {}});
+            assert!(
+                matches!(
+                    check_account_state(&locked),
+                    Err(DeezerError::AccountLocked(_))
+                ),
+                "{}",
+                key
+            );
+        }
+
+        for key in REAUTH_REQUIRED_ERRORS {
+            let reauth = json!({"error": {key: "Login again"}, "results": {}});
+            assert!(
+                matches!(
+                    check_account_state(&reauth),
+                    Err(DeezerError::ReauthRequired(_))
+                ),
+                "{}",
+                key
+            );
+        }
+
+        // Unrelated keys that merely contain similar words are not account states
+        for key in [
+            "VALID_TOKEN_REQUIRED",
+            "EMAIL_VERIFICATION_SENT",
+            "BLOCKED_CONTENT",
+        ] {
+            let other = json!({"error": {key: "Other"}, "results": {"USER": {"USER_ID": 0}}});
+            assert!(
+                matches!(
+                    check_account_state(&other),
+                    Err(DeezerError::BadCredentials(_))
+                ),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_arl_from_cookies() {
+        let contents = "# Netscape HTTP Cookie File\n\
+            .example.com\tTRUE\t/\tFALSE\t0\tarl\tnot-this-one\n\
+            .deezer.com\tTRUE\t/\tFALSE\t1999999999\tsid\tfr123\n\
+            #HttpOnly_.deezer.com\tTRUE\t/\tTRUE\t1999999999\tarl\tabc123\n";
+        assert_eq!(arl_from_cookies(contents), Some("abc123".to_string()));
+
+        let no_arl = ".deezer.com\tTRUE\t/\tFALSE\t1999999999\tsid\tfr123\n";
+        assert_eq!(arl_from_cookies(no_arl), None);
+    }
+
+    #[test]
+    fn test_response_preview() {
+        assert_eq!(response_preview("short", 500), "short");
+        assert_eq!(response_preview("abcdef", 3), "abc...");
+        // "é" is two bytes, byte 2 falls inside it
+        assert_eq!(response_preview("aéb", 2), "a...");
+    }
+
+    #[test]
+    fn test_extract_results() {
+        let ok = json!({"error": [], "results": {"SNG_ID": "1"}});
+        assert_eq!(
+            extract_results("song.getData", ok).unwrap(),
+            json!({"SNG_ID": "1"})
+        );
+
+        let failed = json!({"error": {"DATA_ERROR": "song not found"}, "results": null});
+        assert!(matches!(
+            extract_results("song.getData", failed),
+            Err(DeezerError::ApiError(_))
+        ));
+
+        let empty = json!({"error": [], "results": null});
+        assert!(matches!(
+            extract_results("song.getData", empty),
+            Err(DeezerError::NoDataApi(_))
+        ));
+    }
+
+    #[test]
+    fn test_media_url_from() {
+        let stripe = json!({"format": "FLAC", "cipher": {"type": "BF_CBC_STRIPE"}});
+        let media_url = media_url_from(&stripe, "https://cdn/a", "BF_CBC_STRIPE");
+        assert!(media_url.is_stripe());
+        assert_eq!(media_url.key, None);
+        assert_eq!(media_url.encryption_params("1").encryption_type, "blowfish");
+
+        let aes = json!({
+            "format": "MP3_320",
+            "cipher": {"type": "AES_CTR", "key": "00ff", "nonce": "0102"}
+        });
+        let media_url = media_url_from(&aes, "https://cdn/b", "BF_CBC_STRIPE");
+        assert!(!media_url.is_stripe());
+        let params = media_url.encryption_params("1");
+        assert_eq!(params.encryption_type, "aes");
+        assert_eq!(params.key.as_deref(), Some("00ff"));
+        assert_eq!(params.nonce.as_deref(), Some("0102"));
+
+        let missing = media_url_from(&json!({"format": "MP3_128"}), "https://cdn/c", "NONE");
+        assert_eq!(missing.cipher, "NONE");
+        assert_eq!(missing.encryption_params("1").encryption_type, "none");
+    }
+
+    #[test]
+    fn test_song_filesize() {
+        let song = json!({
+            "FILESIZE_MP3_128": "3145728",
+            "FILESIZE_MP3_320": 7864320,
+            "FILESIZE_FLAC": "0"
+        });
+
+        assert_eq!(song_filesize(&song, "MP3_128"), Some(3_145_728));
+        assert_eq!(song_filesize(&song, "MP3_320"), Some(7_864_320));
+        assert_eq!(song_filesize(&song, "FLAC"), None);
+        assert_eq!(song_filesize(&song, "MP4_RA3"), None);
+    }
+
+    #[test]
+    fn test_token_errors() {
+        assert!(is_invalid_token_error(&json!({
+            "error": {"VALID_TOKEN_REQUIRED": "Invalid CSRF token"},
+            "results": {}
+        })));
+        assert!(is_invalid_token_error(&json!({
+            "error": {"GATEWAY_ERROR": "invalid api token"}
+        })));
+        assert!(!is_invalid_token_error(
+            &json!({"error": [], "results": {}})
+        ));
+        assert!(!is_invalid_token_error(&json!({
+            "error": {"DATA_ERROR": "song not found"}
+        })));
+
+        assert!(is_expired_license_error(&json!({
+            "errors": [{"code": 2001, "message": "Invalid license token"}]
+        })));
+        assert!(!is_expired_license_error(&json!({
+            "errors": [{"code": 2002, "message": "Track token has no sufficient rights on requested media"}]
+        })));
+        assert!(!is_expired_license_error(&json!({
+            "errors": [{"code": 1000, "message": "Invalid track token"}]
+        })));
+        assert!(!is_expired_license_error(&json!({"data": []})));
+    }
+
+    #[test]
+    fn test_extract_waveform() {
+        let page = json!({"DATA": {"SNG_ID": "3135556", "WAVEFORM": [0.5, "0.25", 1]}});
+        assert_eq!(extract_waveform(&page), Some(vec![0.5, 0.25, 1.0]));
+
+        let page = json!({"WAVEFORM": [0.1], "DATA": {}});
+        assert_eq!(extract_waveform(&page), Some(vec![0.1]));
+
+        assert_eq!(extract_waveform(&json!({"DATA": {"WAVEFORM": []}})), None);
+        assert_eq!(
+            extract_waveform(&json!({"DATA": {"WAVEFORM": ["x"]}})),
+            None
+        );
+        assert_eq!(extract_waveform(&json!({"DATA": {}})), None);
+    }
+
+    #[test]
+    fn test_is_followed_playlist() {
+        let own = json!({"PLAYLIST_ID": "1", "PARENT_USER_ID": "42"});
+        let followed = json!({"PLAYLIST_ID": "2", "PARENT_USER_ID": 7});
+        assert!(!is_followed_playlist(&own, 42));
+        assert!(is_followed_playlist(&followed, 42));
+        assert!(!is_followed_playlist(&json!({"PLAYLIST_ID": "3"}), 42));
+    }
+
+    #[test]
+    fn test_is_last_page() {
+        assert!(is_last_page(0, 100, None));
+        assert!(!is_last_page(500, 500, Some(1200)));
+        assert!(is_last_page(200, 1200, Some(1200)));
+        // Short pages don't end the loop while the total isn't reached
+        assert!(!is_last_page(100, 100, Some(1200)));
+        assert!(is_last_page(100, 100, None));
+    }
+
     #[test]
     fn test_song_url_generation() {
         let url = GatewayApi::get_song_url(2, "abc123");
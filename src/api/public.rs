@@ -3,20 +3,98 @@
 //! This module provides a client for the public Deezer API (api.deezer.com).
 //! No authentication is required for most operations.
 
-use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{Client, ClientBuilder};
 use serde_json::Value;
 use tracing::{debug, error, warn};
 
+use crate::cache::{Cache, MemoryCache};
 use crate::converters;
 use crate::error::{DeezerError, Result};
-use crate::models::{Album, Artist, Playlist, Track};
+use crate::link::validate_deezer_id;
+use crate::models::{Album, Artist, ArtistAlbum, Playlist, Track};
+use crate::throttle::Throttle;
 
 /// Base URL for the Deezer public API.
 const API_BASE_URL: &str = "https://api.deezer.com/";
 
-/// Cover image URL template.
-const COVER_URL_TEMPLATE: &str =
-    "https://e-cdns-images.dzcdn.net/images/cover/{md5}/{size}-000000-80-0-0.jpg";
+/// Host every pagination URL must point at.
+const API_HOST: &str = "api.deezer.com";
+
+/// How many times a request is retried after Deezer's quota error.
+const QUOTA_RETRIES: u32 = 3;
+
+/// Wait before retrying after Deezer's quota error.
+///
+/// Deezer counts requests over windows of 5 seconds.
+const QUOTA_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How long album data fetched to enrich tracks is cached.
+const ALBUM_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Number of tracks requested per page of an album's track list.
+const ALBUM_TRACKS_PAGE_SIZE: u64 = 100;
+
+/// Default number of album track pages fetched concurrently.
+const DEFAULT_PAGE_CONCURRENCY: usize = 4;
+
+/// Maximum number of track requests in flight for [`DeezerApi::get_tracks`].
+const TRACK_BATCH_CONCURRENCY: usize = 8;
+
+/// A page of search results with its navigation cursors.
+#[derive(Debug, Clone)]
+pub struct SearchPage<T> {
+    /// Results on this page.
+    pub data: Vec<T>,
+    /// Total number of results across all pages.
+    pub total: u64,
+    /// URL of the next page, if any.
+    pub next: Option<String>,
+    /// URL of the previous page, if any.
+    pub prev: Option<String>,
+}
+
+impl<T> SearchPage<T> {
+    /// Check whether there is a next page.
+    pub fn has_next(&self) -> bool {
+        self.next.is_some()
+    }
+}
+
+impl SearchPage<Value> {
+    /// Build a page from a raw search response.
+    fn from_response(response: &Value) -> Self {
+        let cursor = |key: &str| {
+            response
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+
+        Self {
+            data: response
+                .get("data")
+                .and_then(|d| d.as_array())
+                .cloned()
+                .unwrap_or_default(),
+            total: response.get("total").and_then(|t| t.as_u64()).unwrap_or(0),
+            next: cursor("next"),
+            prev: cursor("prev"),
+        }
+    }
+
+    /// Convert the results with `parse`, dropping those that fail.
+    fn parse_with<T>(self, parse: fn(&Value) -> Result<T>) -> SearchPage<T> {
+        SearchPage {
+            data: self.data.iter().filter_map(|v| parse(v).ok()).collect(),
+            total: self.total,
+            next: self.next,
+            prev: self.prev,
+        }
+    }
+}
 
 /// Public Deezer API client.
 ///
@@ -26,7 +104,7 @@ const COVER_URL_TEMPLATE: &str =
 /// # Example
 ///
 /// ```rust,no_run
-/// use Rusteer::DeezerApi;
+/// use rusteer::DeezerApi;
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -40,7 +118,11 @@ const COVER_URL_TEMPLATE: &str =
 pub struct DeezerApi {
     client: Client,
     /// Cache for album data to avoid redundant requests.
-    album_cache: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, Value>>>,
+    cache: Arc<dyn Cache>,
+    /// Number of album track pages fetched concurrently.
+    page_concurrency: usize,
+    /// Limit on requests per second, shared by clones of the client.
+    rate_limit: Option<Arc<Throttle>>,
 }
 
 impl Default for DeezerApi {
@@ -52,64 +134,146 @@ impl Default for DeezerApi {
 impl DeezerApi {
     /// Create a new Deezer API client.
     pub fn new() -> Self {
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
+        let client = Self::client_builder()
             .build()
             .expect("Failed to create HTTP client");
 
+        Self::with_client(client)
+    }
+
+    /// Get a client builder set up like the one of [`new`](Self::new).
+    pub fn client_builder() -> ClientBuilder {
+        Client::builder()
+            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
+    }
+
+    /// Create a new Deezer API client sending its requests with `client`.
+    ///
+    /// Lets the connection pool, timeouts or proxy be configured, and the
+    /// client be shared with other code.
+    pub fn with_client(client: Client) -> Self {
         Self {
             client,
-            album_cache: std::sync::Arc::new(tokio::sync::RwLock::new(
-                std::collections::HashMap::new(),
-            )),
+            cache: Arc::new(MemoryCache::new()),
+            page_concurrency: DEFAULT_PAGE_CONCURRENCY,
+            rate_limit: None,
         }
     }
 
-    /// Make a GET request to the Deezer API.
-    async fn get_api(&self, endpoint: &str) -> Result<Value> {
-        let url = format!("{}{}", API_BASE_URL, endpoint);
-        debug!("GET {}", url);
+    /// Set how many pages of an album's track list are fetched at once.
+    ///
+    /// Default is 4. Pages are requested by index, and tracks are sorted by
+    /// disc and position afterwards, so the order doesn't depend on which
+    /// page arrives first. Values below 1 are treated as 1.
+    pub fn set_page_concurrency(&mut self, concurrency: usize) {
+        self.page_concurrency = concurrency;
+    }
 
-        let response = self.client.get(&url).send().await?;
-        let data: Value = response.json().await?;
+    /// Limit how many API requests are sent per second.
+    ///
+    /// Requests are spaced out to stay under the limit, which is shared by
+    /// every clone of this client. Independently of the limit, requests
+    /// failing with Deezer's quota error are retried a few times after a
+    /// pause. Pass `None` to remove the limit (default).
+    pub fn set_rate_limit(&mut self, requests_per_second: Option<f64>) {
+        self.rate_limit = requests_per_second
+            .filter(|&rate| rate > 0.0)
+            .map(|rate| Arc::new(Throttle::new(rate, 1.0)));
+    }
 
-        // Check for API errors
-        if let Some(error) = data.get("error") {
-            let error_msg = error
-                .get("message")
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown error");
-            error!("Deezer API error: {}", error_msg);
-            return Err(DeezerError::ApiError(error_msg.to_string()));
+    /// Get the limit on API requests per second.
+    pub fn rate_limit(&self) -> Option<f64> {
+        self.rate_limit.as_ref().map(|throttle| throttle.rate())
+    }
+
+    /// Wait until the rate limit allows another request.
+    async fn wait_for_rate_limit(&self) {
+        if let Some(throttle) = &self.rate_limit {
+            throttle.acquire(1.0).await;
         }
+    }
 
-        Ok(data)
+    /// Send further requests with `client`.
+    ///
+    /// To use a proxy or timeouts, configure them on a builder from
+    /// [`client_builder`](Self::client_builder), or use
+    /// [`Rusteer::set_proxy`](crate::Rusteer::set_proxy).
+    pub fn set_client(&mut self, client: Client) {
+        self.client = client;
+    }
+
+    /// Use `cache` for album data instead of the client's own in-memory cache.
+    pub fn set_cache(&mut self, cache: Arc<dyn Cache>) {
+        self.cache = cache;
+    }
+
+    /// Make a GET request to the Deezer API.
+    async fn get_api(&self, endpoint: &str) -> Result<Value> {
+        self.get_api_url(&format!("{}{}", API_BASE_URL, endpoint))
+            .await
+    }
+
+    /// Make a GET request to a full Deezer API URL.
+    async fn get_api_url(&self, url: &str) -> Result<Value> {
+        debug!("GET {}", url);
+        self.send_api(url, &[]).await
+    }
+
+    /// Fetch the next page of a paginated response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidLink` if `url` is not a Deezer API URL, see
+    /// [`validate_next_url`], and the errors of any other API request.
+    async fn get_next_page(&self, url: &str) -> Result<Value> {
+        let url = validate_next_url(url)?;
+        self.get_api_url(url.as_str()).await
     }
 
     /// Make a GET request with query parameters.
     async fn get_api_with_params(&self, endpoint: &str, params: &[(&str, &str)]) -> Result<Value> {
         let url = format!("{}{}", API_BASE_URL, endpoint);
         debug!("GET {} with params: {:?}", url, params);
+        self.send_api(&url, params).await
+    }
 
-        let response = self.client.get(&url).query(params).send().await?;
-        let data: Value = response.json().await?;
+    /// Send an API request, honoring the rate limit and retrying on quota errors.
+    async fn send_api(&self, url: &str, params: &[(&str, &str)]) -> Result<Value> {
+        let mut attempt = 0;
+        loop {
+            self.wait_for_rate_limit().await;
 
-        if let Some(error) = data.get("error") {
-            let error_msg = error
-                .get("message")
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown error");
-            error!("Deezer API error: {}", error_msg);
-            return Err(DeezerError::ApiError(error_msg.to_string()));
-        }
+            let response = self.client.get(url).query(params).send().await?;
+            let data: Value = response.json().await?;
+
+            // Check for API errors
+            let Some(error) = data.get("error") else {
+                return Ok(data);
+            };
+
+            let error = api_error(error);
+            if matches!(error, DeezerError::QuotaExceeded) && attempt < QUOTA_RETRIES {
+                attempt += 1;
+                warn!(
+                    "Deezer API quota exceeded, retrying in {:?} ({}/{})",
+                    QUOTA_RETRY_DELAY, attempt, QUOTA_RETRIES
+                );
+                tokio::time::sleep(QUOTA_RETRY_DELAY).await;
+                continue;
+            }
 
-        Ok(data)
+            error!("Deezer API error: {}", error);
+            return Err(error);
+        }
     }
 
-    /// Get a track by ID.
+    /// Get a track by ID or ISRC.
     ///
-    /// Also fetches full album data to enrich the track metadata.
+    /// Accepts a numeric ID or the "isrc:CODE" format (the prefix is
+    /// case-insensitive). Also fetches full album data to enrich the track
+    /// metadata.
     pub async fn get_track(&self, track_id: &str) -> Result<Track> {
+        let track_id = track_reference(track_id)?;
         let mut track_json = self.get_api(&format!("track/{}", track_id)).await?;
 
         // Enrich with album data if available
@@ -118,22 +282,18 @@ impl DeezerApi {
             .and_then(|a| a.get("id"))
             .and_then(|id| id.as_u64())
         {
-            let album_id_str = album_id.to_string();
+            let cache_key = format!("album:{}", album_id);
 
             // Check cache first
-            let cached = {
-                let cache = self.album_cache.read().await;
-                cache.get(&album_id_str).cloned()
-            };
-
-            let full_album = match cached {
+            let full_album = match self.cache.get(&cache_key).await {
                 Some(album) => album,
                 None => {
                     match self.get_api(&format!("album/{}", album_id)).await {
                         Ok(album_json) => {
                             // Cache the album
-                            let mut cache = self.album_cache.write().await;
-                            cache.insert(album_id_str.clone(), album_json.clone());
+                            self.cache
+                                .insert(&cache_key, album_json.clone(), Some(ALBUM_CACHE_TTL))
+                                .await;
                             album_json
                         }
                         Err(e) => {
@@ -176,6 +336,16 @@ impl DeezerApi {
                             album_obj.insert("record_type".to_string(), record_type.clone());
                         }
 
+                        // Copy label
+                        if let Some(label) = full_album.get("label") {
+                            album_obj.insert("label".to_string(), label.clone());
+                        }
+
+                        // Copy the track list, for various-artists detection
+                        if let Some(tracks) = full_album.get("tracks") {
+                            album_obj.insert("tracks".to_string(), tracks.clone());
+                        }
+
                         // Copy contributors
                         if let Some(contributors) = full_album.get("contributors") {
                             album_obj.insert("contributors".to_string(), contributors.clone());
@@ -198,6 +368,21 @@ impl DeezerApi {
         converters::parse_track(&track_json)
     }
 
+    /// Get several tracks by ID.
+    ///
+    /// Requests run concurrently (at most 8 at a time) and share the album
+    /// cache used by [`get_track`](Self::get_track). Results are in the same
+    /// order as `track_ids`, each with its own error.
+    pub async fn get_tracks(&self, track_ids: &[&str]) -> Vec<Result<Track>> {
+        use futures_util::StreamExt;
+
+        futures_util::stream::iter(track_ids)
+            .map(|track_id| self.get_track(track_id))
+            .buffered(TRACK_BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
     /// Get raw track JSON by ID or ISRC.
     ///
     /// Accepts numeric ID or "isrc:CODE" format.
@@ -207,8 +392,13 @@ impl DeezerApi {
 
     /// Get an album by ID.
     ///
-    /// Handles pagination for albums with more than 25 tracks.
+    /// Handles pagination for albums with more than 25 tracks, fetching
+    /// track list pages concurrently. Fails if any page can't be fetched,
+    /// rather than returning an album with tracks missing.
     pub async fn get_album(&self, album_id: &str) -> Result<Album> {
+        use futures_util::StreamExt;
+
+        let album_id = validate_deezer_id(album_id)?;
         let mut album_json = self.get_api(&format!("album/{}", album_id)).await?;
 
         // Check for API errors
@@ -233,7 +423,10 @@ impl DeezerApi {
                 })?;
 
         // Fetch detailed tracks from dedicated endpoint
-        let tracks_url = format!("album/{}/tracks?limit=100", numeric_album_id);
+        let tracks_url = format!(
+            "album/{}/tracks?limit={}",
+            numeric_album_id, ALBUM_TRACKS_PAGE_SIZE
+        );
         let mut all_tracks = Vec::new();
 
         match self.get_api(&tracks_url).await {
@@ -242,37 +435,35 @@ impl DeezerApi {
                     all_tracks.extend(data.iter().cloned());
                 }
 
-                // Handle pagination
-                let mut next_url = tracks_response
-                    .get("next")
-                    .and_then(|n| n.as_str())
-                    .map(|s| s.to_string());
-
-                while let Some(url) = next_url {
-                    match self.client.get(&url).send().await {
-                        Ok(response) => match response.json::<Value>().await {
-                            Ok(next_data) => {
-                                if let Some(data) = next_data.get("data").and_then(|d| d.as_array())
-                                {
-                                    all_tracks.extend(data.iter().cloned());
-                                }
-                                next_url = next_data
-                                    .get("next")
-                                    .and_then(|n| n.as_str())
-                                    .map(|s| s.to_string());
-                            }
-                            Err(e) => {
-                                error!("Error parsing pagination response: {}", e);
-                                break;
-                            }
-                        },
-                        Err(e) => {
-                            error!("Error fetching next page: {}", e);
-                            break;
-                        }
+                // Fetch the remaining pages concurrently by index
+                let total = tracks_response
+                    .get("total")
+                    .and_then(|t| t.as_u64())
+                    .unwrap_or(all_tracks.len() as u64);
+                let indices =
+                    remaining_page_indices(total, all_tracks.len() as u64, ALBUM_TRACKS_PAGE_SIZE);
+
+                let pages: Vec<_> = futures_util::stream::iter(indices)
+                    .map(|index| async move {
+                        let endpoint = format!(
+                            "album/{}/tracks?limit={}&index={}",
+                            numeric_album_id, ALBUM_TRACKS_PAGE_SIZE, index
+                        );
+                        self.get_api(&endpoint).await
+                    })
+                    .buffered(self.page_concurrency.max(1))
+                    .collect()
+                    .await;
+
+                // A missing page would silently drop tracks from downloads
+                for page in pages {
+                    if let Some(data) = page?.get("data").and_then(|d| d.as_array()) {
+                        all_tracks.extend(data.iter().cloned());
                     }
                 }
 
+                sort_album_tracks(&mut all_tracks);
+
                 // Replace tracks in album JSON
                 if let Some(tracks) = album_json.get_mut("tracks") {
                     if let Some(tracks_obj) = tracks.as_object_mut() {
@@ -306,29 +497,15 @@ impl DeezerApi {
                             let mut next_url = Some(next.to_string());
 
                             while let Some(url) = next_url {
-                                match self.client.get(&url).send().await {
-                                    Ok(response) => match response.json::<Value>().await {
-                                        Ok(next_data) => {
-                                            if let Some(data) =
-                                                next_data.get("data").and_then(|d| d.as_array())
-                                            {
-                                                all_tracks.extend(data.iter().cloned());
-                                            }
-                                            next_url = next_data
-                                                .get("next")
-                                                .and_then(|n| n.as_str())
-                                                .map(|s| s.to_string());
-                                        }
-                                        Err(e) => {
-                                            error!("Error parsing pagination: {}", e);
-                                            break;
-                                        }
-                                    },
-                                    Err(e) => {
-                                        error!("Error fetching next page: {}", e);
-                                        break;
-                                    }
+                                let next_data = self.get_next_page(&url).await?;
+                                if let Some(data) = next_data.get("data").and_then(|d| d.as_array())
+                                {
+                                    all_tracks.extend(data.iter().cloned());
                                 }
+                                next_url = next_data
+                                    .get("next")
+                                    .and_then(|n| n.as_str())
+                                    .map(|s| s.to_string());
                             }
 
                             if let Some(tracks) = album_json.get_mut("tracks") {
@@ -342,7 +519,18 @@ impl DeezerApi {
             }
         }
 
-        converters::parse_album(&album_json)
+        let album = converters::parse_album(&album_json)?;
+        if album.missing_tracks() > 0 {
+            warn!(
+                "Album {} lists {} of {} tracks; {} may be region-restricted",
+                album_id,
+                album.tracks.len(),
+                album.total_tracks,
+                album.missing_tracks()
+            );
+        }
+
+        Ok(album)
     }
 
     /// Get raw album JSON by ID or UPC.
@@ -352,10 +540,47 @@ impl DeezerApi {
         self.get_api(&format!("album/{}", album_id_or_upc)).await
     }
 
+    /// Get the number of tracks of an album without paginating its tracks.
+    ///
+    /// A single request, for listings that only show counts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AlbumNotFound` if the album has no track count.
+    pub async fn get_album_track_count(&self, album_id: &str) -> Result<u32> {
+        let album_id = validate_deezer_id(album_id)?;
+        let album_json = self.get_api(&format!("album/{}", album_id)).await?;
+        track_count(&album_json).ok_or_else(|| DeezerError::AlbumNotFound(album_id.to_string()))
+    }
+
+    /// Get the number of tracks of a playlist without paginating its tracks.
+    ///
+    /// A single request, for listings that only show counts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlaylistNotFound` if the playlist has no track count.
+    pub async fn get_playlist_track_count(&self, playlist_id: &str) -> Result<u32> {
+        let playlist_id = validate_deezer_id(playlist_id)?;
+        let playlist_json = self.get_api(&format!("playlist/{}", playlist_id)).await?;
+        track_count(&playlist_json)
+            .ok_or_else(|| DeezerError::PlaylistNotFound(playlist_id.to_string()))
+    }
+
+    /// Get a playlist's metadata without paging through its tracks.
+    ///
+    /// Only the first page of tracks is included.
+    pub async fn get_playlist_info(&self, playlist_id: &str) -> Result<Playlist> {
+        let playlist_id = validate_deezer_id(playlist_id)?;
+        let playlist_json = self.get_api(&format!("playlist/{}", playlist_id)).await?;
+        converters::parse_playlist(&playlist_json)
+    }
+
     /// Get a playlist by ID.
     ///
     /// Handles pagination for large playlists.
     pub async fn get_playlist(&self, playlist_id: &str) -> Result<Playlist> {
+        let playlist_id = validate_deezer_id(playlist_id)?;
         let mut playlist_json = self.get_api(&format!("playlist/{}", playlist_id)).await?;
 
         // Handle pagination for tracks
@@ -370,28 +595,14 @@ impl DeezerApi {
                 let mut next_url = Some(next.to_string());
 
                 while let Some(url) = next_url {
-                    match self.client.get(&url).send().await {
-                        Ok(response) => match response.json::<Value>().await {
-                            Ok(next_data) => {
-                                if let Some(data) = next_data.get("data").and_then(|d| d.as_array())
-                                {
-                                    all_tracks.extend(data.iter().cloned());
-                                }
-                                next_url = next_data
-                                    .get("next")
-                                    .and_then(|n| n.as_str())
-                                    .map(|s| s.to_string());
-                            }
-                            Err(e) => {
-                                error!("Error parsing pagination: {}", e);
-                                break;
-                            }
-                        },
-                        Err(e) => {
-                            error!("Error fetching next page: {}", e);
-                            break;
-                        }
+                    let next_data = self.get_next_page(&url).await?;
+                    if let Some(data) = next_data.get("data").and_then(|d| d.as_array()) {
+                        all_tracks.extend(data.iter().cloned());
                     }
+                    next_url = next_data
+                        .get("next")
+                        .and_then(|n| n.as_str())
+                        .map(|s| s.to_string());
                 }
 
                 if let Some(tracks_obj) = tracks.as_object_mut() {
@@ -405,12 +616,49 @@ impl DeezerApi {
 
     /// Get an artist by ID.
     pub async fn get_artist(&self, artist_id: &str) -> Result<Artist> {
+        let artist_id = validate_deezer_id(artist_id)?;
         let artist_json = self.get_api(&format!("artist/{}", artist_id)).await?;
         converters::parse_artist(&artist_json)
     }
 
+    /// Get artist metadata by ID, with the full discography in `albums`.
+    ///
+    /// Costs one request per page of albums on top of
+    /// [`get_artist`](Self::get_artist), which leaves `albums` empty.
+    pub async fn get_artist_with_albums(&self, artist_id: &str) -> Result<Artist> {
+        let mut artist = self.get_artist(artist_id).await?;
+        artist.albums = self.get_artist_albums(artist_id).await?;
+        Ok(artist)
+    }
+
+    /// Get all albums of an artist's discography.
+    pub async fn get_artist_albums(&self, artist_id: &str) -> Result<Vec<ArtistAlbum>> {
+        let artist_id = validate_deezer_id(artist_id)?;
+        let mut response = self
+            .get_api(&format!("artist/{}/albums", artist_id))
+            .await?;
+        let mut albums = Vec::new();
+
+        loop {
+            if let Some(data) = response.get("data").and_then(|d| d.as_array()) {
+                albums.extend(
+                    data.iter()
+                        .filter_map(|a| converters::parse_artist_album(a).ok()),
+                );
+            }
+
+            match response.get("next").and_then(|n| n.as_str()) {
+                Some(next) => response = self.get_next_page(next).await?,
+                None => break,
+            }
+        }
+
+        Ok(albums)
+    }
+
     /// Get an artist's top tracks.
     pub async fn get_artist_top_tracks(&self, artist_id: &str, limit: u32) -> Result<Vec<Track>> {
+        let artist_id = validate_deezer_id(artist_id)?;
         let response = self
             .get_api(&format!("artist/{}/top?limit={}", artist_id, limit))
             .await?;
@@ -420,10 +668,7 @@ impl DeezerApi {
             .and_then(|d| d.as_array())
             .ok_or_else(|| DeezerError::NoDataApi("No tracks data".to_string()))?;
 
-        tracks_data
-            .iter()
-            .map(|t| converters::parse_track(t))
-            .collect()
+        tracks_data.iter().map(converters::parse_track).collect()
     }
 
     /// Search for tracks.
@@ -504,6 +749,52 @@ impl DeezerApi {
             .pipe(Ok)
     }
 
+    /// Search for tracks, starting at result `index`.
+    ///
+    /// Returns the page with the total number of results, for paging
+    /// through them with increasing `index`.
+    pub async fn search_tracks_paged(
+        &self,
+        query: &str,
+        limit: u32,
+        index: u32,
+    ) -> Result<SearchPage<Track>> {
+        let page = self
+            .search_raw_page("search/track", query, limit, index)
+            .await?;
+        Ok(page.parse_with(converters::parse_track))
+    }
+
+    /// Search for albums, starting at result `index`.
+    ///
+    /// See [`search_tracks_paged`](Self::search_tracks_paged).
+    pub async fn search_albums_paged(
+        &self,
+        query: &str,
+        limit: u32,
+        index: u32,
+    ) -> Result<SearchPage<Album>> {
+        let page = self
+            .search_raw_page("search/album", query, limit, index)
+            .await?;
+        Ok(page.parse_with(converters::parse_album))
+    }
+
+    /// Search for playlists, starting at result `index`.
+    ///
+    /// See [`search_tracks_paged`](Self::search_tracks_paged).
+    pub async fn search_playlists_paged(
+        &self,
+        query: &str,
+        limit: u32,
+        index: u32,
+    ) -> Result<SearchPage<Playlist>> {
+        let page = self
+            .search_raw_page("search/playlist", query, limit, index)
+            .await?;
+        Ok(page.parse_with(converters::parse_playlist))
+    }
+
     /// Get raw search results for tracks.
     pub async fn search_tracks_raw(&self, query: &str, limit: u32) -> Result<Vec<Value>> {
         let response = self
@@ -525,6 +816,64 @@ impl DeezerApi {
             .unwrap_or_default())
     }
 
+    /// Get the first page of raw track search results with its cursors.
+    pub async fn search_tracks_raw_page(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<SearchPage<Value>> {
+        self.search_raw_page("search/track", query, limit, 0).await
+    }
+
+    /// Get the first page of raw album search results with its cursors.
+    pub async fn search_albums_raw_page(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<SearchPage<Value>> {
+        self.search_raw_page("search/album", query, limit, 0).await
+    }
+
+    /// Fetch the page a search cursor points to.
+    ///
+    /// `cursor` is the `next` or `prev` URL of a previous [`SearchPage`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidLink` if the cursor is not a Deezer API URL.
+    pub async fn search_raw_cursor(&self, cursor: &str) -> Result<SearchPage<Value>> {
+        let cursor = validate_next_url(cursor)?;
+        let response = self.get_api_url(cursor.as_str()).await?;
+        Ok(SearchPage::from_response(&response))
+    }
+
+    /// Get the page of a raw search starting at result `index`.
+    async fn search_raw_page(
+        &self,
+        endpoint: &str,
+        query: &str,
+        limit: u32,
+        index: u32,
+    ) -> Result<SearchPage<Value>> {
+        let response = self
+            .get_api_with_params(
+                endpoint,
+                &[
+                    ("q", query),
+                    ("limit", &limit.to_string()),
+                    ("index", &index.to_string()),
+                ],
+            )
+            .await?;
+
+        let page = SearchPage::from_response(&response);
+        if page.total == 0 {
+            return Err(DeezerError::NoDataApi(query.to_string()));
+        }
+
+        Ok(page)
+    }
+
     /// Get raw search results for albums.
     pub async fn search_albums_raw(&self, query: &str, limit: u32) -> Result<Vec<Value>> {
         let response = self
@@ -548,14 +897,49 @@ impl DeezerApi {
 
     /// Get an episode by ID (for podcasts).
     pub async fn get_episode(&self, episode_id: &str) -> Result<Value> {
+        let episode_id = validate_deezer_id(episode_id)?;
         self.get_api(&format!("episode/{}", episode_id)).await
     }
 
+    /// Get all episodes of a podcast show (raw JSON).
+    ///
+    /// Handles pagination for shows with many episodes.
+    pub async fn get_show_episodes(&self, show_id: &str) -> Result<Vec<Value>> {
+        let show_id = validate_deezer_id(show_id)?;
+        let response = self
+            .get_api(&format!("podcast/{}/episodes", show_id))
+            .await?;
+
+        let mut all_episodes: Vec<Value> = response
+            .get("data")
+            .and_then(|d| d.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut next_url = response
+            .get("next")
+            .and_then(|n| n.as_str())
+            .map(|s| s.to_string());
+
+        while let Some(url) = next_url {
+            let next_data = self.get_next_page(&url).await?;
+            if let Some(data) = next_data.get("data").and_then(|d| d.as_array()) {
+                all_episodes.extend(data.iter().cloned());
+            }
+            next_url = next_data
+                .get("next")
+                .and_then(|n| n.as_str())
+                .map(|s| s.to_string());
+        }
+
+        Ok(all_episodes)
+    }
+
     /// Get the cover image URL for a given MD5 hash.
+    ///
+    /// See [`converters::cover_image_url`].
     pub fn get_image_url(md5_image: &str, size: &str) -> String {
-        COVER_URL_TEMPLATE
-            .replace("{md5}", md5_image)
-            .replace("{size}", size)
+        converters::cover_image_url(md5_image, size)
     }
 
     /// Fetch cover image bytes.
@@ -588,10 +972,240 @@ trait Pipe: Sized {
 
 impl<T> Pipe for T {}
 
+/// Get the `index` of every page still to fetch after the first `fetched` items.
+fn remaining_page_indices(total: u64, fetched: u64, page_size: u64) -> Vec<u64> {
+    if fetched == 0 {
+        return Vec::new();
+    }
+    (fetched..total).step_by(page_size as usize).collect()
+}
+
+/// Convert the `error` object of an API response into an error.
+///
+/// Maps Deezer's error codes to the matching variants: 4 (quota) to
+/// `QuotaExceeded`, 200 and 300 (permission, invalid token) to
+/// `BadCredentials` and 800 (no data) to `NoDataApi`. Other codes become
+/// `ApiError`.
+fn api_error(error: &Value) -> DeezerError {
+    let message = error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("Unknown error")
+        .to_string();
+
+    // Codes are numbers, but accept strings as well
+    let code = error.get("code").and_then(|c| {
+        c.as_u64()
+            .or_else(|| c.as_str().and_then(|s| s.parse().ok()))
+    });
+
+    match code {
+        Some(4) => DeezerError::QuotaExceeded,
+        Some(200) | Some(300) => DeezerError::BadCredentials(message),
+        Some(800) => DeezerError::NoDataApi(message),
+        None if message.to_ascii_lowercase().contains("quota") => DeezerError::QuotaExceeded,
+        _ => DeezerError::ApiError(message),
+    }
+}
+
+/// Check that a pagination URL from a response points at the Deezer API.
+///
+/// `http://` URLs are upgraded to HTTPS; any other scheme or host is
+/// rejected so a tampered response can't redirect requests elsewhere.
+fn validate_next_url(url: &str) -> Result<reqwest::Url> {
+    let invalid = || DeezerError::InvalidLink(url.to_string());
+
+    let mut parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    if parsed.host_str() != Some(API_HOST) || parsed.port().is_some() {
+        return Err(invalid());
+    }
+
+    match parsed.scheme() {
+        "https" => {}
+        "http" => parsed.set_scheme("https").map_err(|_| invalid())?,
+        _ => return Err(invalid()),
+    }
+
+    Ok(parsed)
+}
+
+/// Validate a track reference: a Deezer ID or an `isrc:CODE` lookup.
+///
+/// ISRC lookups are returned with a lowercase `isrc:` prefix, as the API
+/// expects.
+fn track_reference(track_id: &str) -> Result<String> {
+    let trimmed = track_id.trim();
+    let isrc = trimmed
+        .get(..5)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("isrc:"))
+        .map(|_| trimmed[5..].trim());
+
+    match isrc {
+        Some(code) if !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            Ok(format!("isrc:{}", code))
+        }
+        Some(_) => Err(DeezerError::InvalidLink(format!(
+            "Invalid ISRC: {}",
+            track_id
+        ))),
+        None => validate_deezer_id(track_id).map(str::to_string),
+    }
+}
+
+/// Read the `nb_tracks` field of an album or playlist object.
+fn track_count(json: &Value) -> Option<u32> {
+    json.get("nb_tracks")?.as_u64()?.try_into().ok()
+}
+
+/// Sort album tracks by disc number, then track position.
+///
+/// The sort is stable, so tracks without positions keep their relative order.
+fn sort_album_tracks(tracks: &mut [Value]) {
+    let position = |track: &Value, key: &str| track.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    tracks.sort_by_key(|track| {
+        (
+            position(track, "disk_number"),
+            position(track, "track_position"),
+        )
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_track_reference() {
+        assert_eq!(track_reference(" 3135556 ").unwrap(), "3135556");
+        assert_eq!(
+            track_reference("isrc:GBDUW0000059").unwrap(),
+            "isrc:GBDUW0000059"
+        );
+        assert_eq!(
+            track_reference("ISRC:GBDUW0000059").unwrap(),
+            "isrc:GBDUW0000059"
+        );
+
+        assert!(track_reference("isrc:").is_err());
+        assert!(track_reference("isrc:GB/DUW").is_err());
+        assert!(track_reference("track/3135556").is_err());
+    }
+
+    #[test]
+    fn test_validate_next_url() {
+        let next = "https://api.deezer.com/playlist/908622995/tracks?index=25";
+        assert_eq!(validate_next_url(next).unwrap().as_str(), next);
+
+        let upgraded =
+            validate_next_url("http://api.deezer.com/artist/27/albums?index=25").unwrap();
+        assert_eq!(
+            upgraded.as_str(),
+            "https://api.deezer.com/artist/27/albums?index=25"
+        );
+
+        assert!(validate_next_url("https://evil.example/album/1/tracks").is_err());
+        assert!(validate_next_url("https://api.deezer.com.evil.example/tracks").is_err());
+        assert!(validate_next_url("ftp://api.deezer.com/tracks").is_err());
+        assert!(validate_next_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_track_count() {
+        let album = serde_json::json!({"id": 302127, "nb_tracks": 14, "tracks": {"data": []}});
+        assert_eq!(track_count(&album), Some(14));
+        assert_eq!(track_count(&serde_json::json!({"id": 1})), None);
+    }
+
+    #[test]
+    fn test_api_error_codes() {
+        let quota =
+            serde_json::json!({"type": "Exception", "message": "Quota limit exceeded", "code": 4});
+        assert!(matches!(api_error(&quota), DeezerError::QuotaExceeded));
+
+        let no_data =
+            serde_json::json!({"type": "DataException", "message": "no data", "code": 800});
+        assert!(matches!(api_error(&no_data), DeezerError::NoDataApi(m) if m == "no data"));
+
+        let token = serde_json::json!({"type": "OAuthException", "message": "Invalid OAuth access token.", "code": "300"});
+        assert!(matches!(api_error(&token), DeezerError::BadCredentials(_)));
+
+        let param = serde_json::json!({"type": "ParameterException", "message": "Wrong parameter", "code": 500});
+        assert!(matches!(api_error(&param), DeezerError::ApiError(m) if m == "Wrong parameter"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_shared_by_clones() {
+        let mut api = DeezerApi::new();
+        api.set_rate_limit(Some(20.0));
+        let clone = api.clone();
+        assert_eq!(clone.rate_limit(), Some(20.0));
+
+        // One request is free, the next two wait ~50ms each whichever clone sends them
+        let start = std::time::Instant::now();
+        api.wait_for_rate_limit().await;
+        clone.wait_for_rate_limit().await;
+        api.wait_for_rate_limit().await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+
+        api.set_rate_limit(None);
+        assert_eq!(api.rate_limit(), None);
+    }
+
+    #[test]
+    fn test_search_page_from_response() {
+        let response = serde_json::json!({
+            "data": [{"id": 1}, {"id": 2}],
+            "total": 40,
+            "next": "https://api.deezer.com/search/track?q=daft&index=2"
+        });
+
+        let page = SearchPage::from_response(&response);
+        assert_eq!(page.data.len(), 2);
+        assert_eq!(page.total, 40);
+        assert!(page.has_next());
+        assert_eq!(page.prev, None);
+
+        let parsed = page.parse_with(|v| {
+            v.get("id")
+                .and_then(|id| id.as_u64())
+                .filter(|&id| id > 1)
+                .ok_or_else(|| DeezerError::ApiError("bad id".to_string()))
+        });
+        assert_eq!(parsed.data, vec![2]);
+        assert_eq!(parsed.total, 40);
+    }
+
+    #[test]
+    fn test_remaining_page_indices() {
+        assert_eq!(remaining_page_indices(250, 100, 100), vec![100, 200]);
+        assert_eq!(remaining_page_indices(100, 100, 100), Vec::<u64>::new());
+        assert_eq!(remaining_page_indices(50, 0, 100), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_sort_album_tracks() {
+        let mut tracks = vec![
+            serde_json::json!({"id": 3, "disk_number": 2, "track_position": 1}),
+            serde_json::json!({"id": 2, "disk_number": 1, "track_position": 2}),
+            serde_json::json!({"id": 1, "disk_number": 1, "track_position": 1}),
+        ];
+        sort_album_tracks(&mut tracks);
+
+        let ids: Vec<_> = tracks.iter().map(|t| t["id"].as_u64().unwrap()).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_get_tracks_keeps_per_id_errors() {
+        let api = DeezerApi::new();
+        let results = api.get_tracks(&["", "track/1", "-5"]).await;
+
+        assert_eq!(results.len(), 3);
+        assert!(results
+            .iter()
+            .all(|r| matches!(r, Err(DeezerError::InvalidLink(_)))));
+    }
+
     #[test]
     fn test_image_url_generation() {
         let url = DeezerApi::get_image_url("abcd1234", "1200x1200");
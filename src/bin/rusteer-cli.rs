@@ -1,5 +1,7 @@
 use clap::{Parser, Subcommand, ValueEnum};
-use rusteer::{DownloadQuality, Rusteer};
+use rusteer::{
+    is_short_link, parse_deezer_url, DeezerLink, DownloadQuality, DownloadResult, Rusteer,
+};
 use std::path::PathBuf;
 
 // Update to add Stream enum command
@@ -27,6 +29,7 @@ struct Cli {
 enum Quality {
     Flac,
     Mp3_320,
+    Mp3_256,
     Mp3_128,
 }
 
@@ -35,6 +38,7 @@ impl From<Quality> for DownloadQuality {
         match q {
             Quality::Flac => DownloadQuality::Flac,
             Quality::Mp3_320 => DownloadQuality::Mp3_320,
+            Quality::Mp3_256 => DownloadQuality::Mp3_256,
             Quality::Mp3_128 => DownloadQuality::Mp3_128,
         }
     }
@@ -47,7 +51,7 @@ enum Commands {
         /// URL or ID of the content to download
         id_or_url: String,
 
-        /// Type of content (track, album, playlist) - optional, will try to auto-detect if URL provided
+        /// Type of content (track, album, playlist, episode, show) - optional, will try to auto-detect if URL provided
         #[arg(short, long)]
         r#type: Option<ContentType>,
     },
@@ -76,6 +80,8 @@ enum ContentType {
     Track,
     Album,
     Playlist,
+    Episode,
+    Show,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -84,6 +90,18 @@ enum SearchType {
     Album,
 }
 
+/// Print the quality a track was downloaded in, warning on downgrades.
+fn print_quality(result: &DownloadResult, requested: DownloadQuality) {
+    println!("   Quality: {}", result.quality.format());
+    if result.quality != requested {
+        println!(
+            "   Warning: requested {} but got {}",
+            requested.format(),
+            result.quality.format()
+        );
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
@@ -93,77 +111,87 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     rusteer.set_output_dir(cli.output.clone());
     rusteer.set_quality(cli.quality.into());
 
+    // Report every track of album/playlist downloads as it completes
+    let requested_quality: DownloadQuality = cli.quality.into();
+    rusteer.set_track_complete_callback(Box::new(move |outcome| match outcome {
+        Ok(result) => {
+            println!("Downloaded: {}", result.title);
+            print_quality(result, requested_quality);
+        }
+        Err(e) => println!("Failed: {}", e),
+    }));
+
     match &cli.command {
         Commands::Download { id_or_url, r#type } => {
-            // Simple heuristic for ID vs URL
-            let id = if id_or_url.contains("deezer.com") {
-                // Extract ID from URL (very basic implementation)
-                id_or_url.split('/').last().unwrap_or(id_or_url)
+            // Extract type and ID from URLs (following share links), treat
+            // anything else as a bare ID
+            let link = if is_short_link(id_or_url) {
+                Some(rusteer.resolve_url(id_or_url).await?)
             } else {
-                id_or_url
+                parse_deezer_url(id_or_url).ok()
             };
-
-            // Determine type if not provided
-            let content_type = match r#type {
-                Some(t) => *t,
-                None => {
-                    if id_or_url.contains("/track/") {
-                        ContentType::Track
-                    } else if id_or_url.contains("/album/") {
-                        ContentType::Album
-                    } else if id_or_url.contains("/playlist/") {
-                        ContentType::Playlist
-                    } else {
-                        // Default to track if unsure
-                        ContentType::Track
+            let (detected_type, id) = if let Some(link) = link {
+                let detected = match &link {
+                    DeezerLink::Track(_) => ContentType::Track,
+                    DeezerLink::Album(_) => ContentType::Album,
+                    DeezerLink::Playlist(_) => ContentType::Playlist,
+                    DeezerLink::Episode(_) => ContentType::Episode,
+                    DeezerLink::Show(_) => ContentType::Show,
+                    DeezerLink::Artist(_) => {
+                        return Err("Artist links cannot be downloaded directly".into());
                     }
-                }
+                };
+                (detected, link.id().to_string())
+            } else {
+                // Default to track if unsure
+                (ContentType::Track, id_or_url.clone())
             };
+            let content_type = r#type.unwrap_or(detected_type);
+            let id = id.as_str();
 
-            println!(
-                "Downloading {} (ID: {})...",
-                match content_type {
-                    ContentType::Track => "track",
-                    ContentType::Album => "album",
-                    ContentType::Playlist => "playlist",
-                },
-                id
-            );
+            match content_type {
+                ContentType::Track => println!("Downloading track (ID: {})...", id),
+                ContentType::Album => println!("Downloading album (ID: {})...", id),
+                ContentType::Playlist => println!("Downloading playlist (ID: {})...", id),
+                ContentType::Episode => println!("Downloading episode (ID: {})...", id),
+                ContentType::Show => println!("Listing episodes of show (ID: {})...", id),
+            }
 
             match content_type {
                 ContentType::Track => {
                     let result = rusteer.download_track(id).await?;
                     println!("Downloaded: {}", result.title);
                     println!("Path: {}", result.path.display());
+                    print_quality(&result, requested_quality);
                 }
                 ContentType::Album => {
                     let result = rusteer.download_album(id).await?;
-                    println!("Album downloaded to: {}", result.directory.display());
-                    println!(
-                        "Successful: {}/{}",
-                        result.successful.len(),
-                        result.total()
-                    );
-                    if !result.failed.is_empty() {
-                        println!("   Failed tracks:");
-                        for (title, err) in result.failed {
-                            println!("   - {}: {}", title, err);
-                        }
-                    }
+                    println!("Album downloaded.");
+                    println!("{}", result.summary());
                 }
                 ContentType::Playlist => {
                     let result = rusteer.download_playlist(id).await?;
-                    println!("Playlist downloaded to: {}", result.directory.display());
-                    println!(
-                        "Successful: {}/{}",
-                        result.successful.len(),
-                        result.total()
-                    );
-                    if !result.failed.is_empty() {
-                        println!("   Failed tracks:");
-                        for (title, err) in result.failed {
-                            println!("   - {}: {}", title, err);
-                        }
+                    println!("Playlist downloaded.");
+                    println!("{}", result.summary());
+                }
+                ContentType::Episode => {
+                    let result = rusteer.download_episode(id).await?;
+                    println!("Downloaded: {} - {}", result.artist, result.title);
+                    println!("Path: {}", result.path.display());
+                }
+                ContentType::Show => {
+                    let episodes = rusteer.get_show_episodes(id).await?;
+                    println!("Show has {} episodes:", episodes.len());
+                    for (i, episode) in episodes.iter().enumerate() {
+                        println!(
+                            "{}. {} (ID: {})",
+                            i + 1,
+                            episode.get("title").and_then(|t| t.as_str()).unwrap_or("?"),
+                            episode
+                                .get("id")
+                                .map(|id| id.to_string())
+                                .unwrap_or_default()
+                        );
                     }
                 }
             }
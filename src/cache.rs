@@ -0,0 +1,130 @@
+//! Response caching shared by the API clients.
+//!
+//! [`DeezerApi`](crate::DeezerApi) and [`GatewayApi`](crate::GatewayApi)
+//! store album data, song data and media URLs in a [`Cache`], so repeated
+//! operations on the same content don't refetch it. The default
+//! [`MemoryCache`] lives in process memory; implement [`Cache`] to plug in
+//! an external store instead.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+/// A key-value store for API responses.
+///
+/// Keys are namespaced by the clients (e.g. `album:302127`,
+/// `song:3135556`). Entries inserted with a TTL must not be returned once
+/// it has elapsed. Methods return boxed futures so the trait can be used as
+/// `Arc<dyn Cache>` and backed by asynchronous stores.
+pub trait Cache: std::fmt::Debug + Send + Sync {
+    /// Get the value stored under `key`, if present and not expired.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<Value>>;
+
+    /// Store `value` under `key`, expiring after `ttl` if given.
+    fn insert<'a>(&'a self, key: &'a str, value: Value, ttl: Option<Duration>)
+        -> BoxFuture<'a, ()>;
+
+    /// Remove the value stored under `key`.
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()>;
+
+    /// Remove every value.
+    fn clear(&self) -> BoxFuture<'_, ()>;
+}
+
+/// In-memory [`Cache`], used by default.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: RwLock<HashMap<String, MemoryEntry>>,
+}
+
+#[derive(Debug)]
+struct MemoryEntry {
+    value: Value,
+    expires_at: Option<Instant>,
+}
+
+impl MemoryEntry {
+    /// Check if the entry is still valid at `now`.
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+impl MemoryCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<Value>> {
+        Box::pin(async move {
+            let now = Instant::now();
+            {
+                let entries = self.entries.read().await;
+                match entries.get(key) {
+                    Some(entry) if entry.is_fresh(now) => return Some(entry.value.clone()),
+                    Some(_) => {}
+                    None => return None,
+                }
+            }
+
+            // Expired: drop it so the map doesn't grow unbounded
+            self.entries.write().await.remove(key);
+            None
+        })
+    }
+
+    fn insert<'a>(
+        &'a self,
+        key: &'a str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let entry = MemoryEntry {
+                value,
+                expires_at: ttl.map(|ttl| Instant::now() + ttl),
+            };
+            self.entries.write().await.insert(key.to_string(), entry);
+        })
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            self.entries.write().await.remove(key);
+        })
+    }
+
+    fn clear(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.entries.write().await.clear();
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_memory_cache_ttl() {
+        let cache = MemoryCache::new();
+        cache.insert("album:1", json!({"id": 1}), None).await;
+        cache
+            .insert("song:2", json!({"SNG_ID": "2"}), Some(Duration::ZERO))
+            .await;
+
+        assert_eq!(cache.get("album:1").await, Some(json!({"id": 1})));
+        assert_eq!(cache.get("song:2").await, None);
+        assert_eq!(cache.get("song:3").await, None);
+
+        cache.remove("album:1").await;
+        assert_eq!(cache.get("album:1").await, None);
+    }
+}
@@ -2,20 +2,33 @@
 //!
 //! This module provides functions to convert raw Deezer API JSON responses
 //! into typed model structures.
-//! 
+//!
+use std::collections::HashSet;
+
 use serde_json::Value;
 
 use crate::error::{DeezerError, Result};
 use crate::models::{
-    album::{Album, AlbumArtist, ArtistTrackAlbum, TrackAlbum},
-    artist::Artist,
+    album::{Album, AlbumArtist, ArtistTrackAlbum, Copyright, TrackAlbum},
+    artist::{AlbumArtist as ArtistAlbum, Artist},
     common::{IDs, Image, ReleaseDate, User},
     playlist::{
         AlbumTrackPlaylist, ArtistAlbumTrackPlaylist, ArtistTrackPlaylist, Playlist, TrackPlaylist,
     },
-    track::{AlbumTrack, ArtistAlbumTrack, ArtistTrack, Track},
+    track::{AlbumTrack, ArtistAlbumTrack, ArtistTrack, Contributor, Track},
 };
 
+/// Cover image URL template.
+const COVER_URL_TEMPLATE: &str =
+    "https://e-cdns-images.dzcdn.net/images/cover/{md5}/{size}-000000-80-0-0.jpg";
+
+/// Build the URL of a cover image from its MD5 hash and a size like `500x500`.
+pub fn cover_image_url(md5_image: &str, size: &str) -> String {
+    COVER_URL_TEMPLATE
+        .replace("{md5}", md5_image)
+        .replace("{size}", size)
+}
+
 /// Parse a release date string into a ReleaseDate struct.
 pub fn parse_release_date(date_str: &str) -> ReleaseDate {
     ReleaseDate::parse(date_str)
@@ -103,6 +116,43 @@ fn get_u64(json: &Value, key: &str) -> u64 {
     json.get(key).and_then(|v| v.as_u64()).unwrap_or(0)
 }
 
+/// Get f64 from JSON (handles both string and numeric values).
+fn get_f64(json: &Value, key: &str) -> Option<f64> {
+    let value = json.get(key)?;
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+/// Get a record label name from JSON, ignoring empty values.
+fn get_label(json: &Value, key: &str) -> Option<String> {
+    json.get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+/// Count the distinct primary artists in an album's `tracks.data` list.
+fn count_track_artists(album_json: &Value) -> u32 {
+    let Some(tracks) = album_json
+        .get("tracks")
+        .and_then(|t| t.get("data"))
+        .and_then(|d| d.as_array())
+    else {
+        return 0;
+    };
+
+    let artists: HashSet<String> = tracks
+        .iter()
+        .filter_map(|track| track.get("artist"))
+        .map(|artist| get_id(artist, "id").unwrap_or_else(|| get_str(artist, "name")))
+        .filter(|key| !key.is_empty())
+        .collect();
+
+    artists.len() as u32
+}
+
 /// Get bool from JSON.
 fn get_bool(json: &Value, key: &str) -> bool {
     json.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
@@ -117,6 +167,15 @@ fn parse_artist_track(json: &Value) -> ArtistTrack {
     }
 }
 
+/// Parse a track contributor, keeping its role.
+fn parse_contributor(json: &Value) -> Contributor {
+    Contributor {
+        name: get_str(json, "name"),
+        role: get_str(json, "role"),
+        ids: IDs::with_deezer(get_id(json, "id").unwrap_or_default()),
+    }
+}
+
 /// Parse an artist in album-track context.
 fn parse_artist_album_track(json: &Value) -> ArtistAlbumTrack {
     ArtistAlbumTrack {
@@ -171,6 +230,8 @@ fn parse_album_track(json: &Value) -> AlbumTrack {
         total_tracks: get_u32(json, "nb_tracks"),
         total_discs: 1, // Will be calculated from tracks if needed
         genres: extract_genres(json),
+        label: get_label(json, "label"),
+        track_artist_count: count_track_artists(json),
     }
 }
 
@@ -188,21 +249,26 @@ pub fn parse_track(json: &Value) -> Result<Track> {
     }
 
     // Add contributors
-    if let Some(contributors) = json.get("contributors").and_then(|c| c.as_array()) {
-        for contributor in contributors {
+    let mut contributors = Vec::new();
+    if let Some(list) = json.get("contributors").and_then(|c| c.as_array()) {
+        for contributor in list {
             let name = get_str(contributor, "name");
             // Skip duplicates
             if !artists.iter().any(|a| a.name == name) {
                 artists.push(parse_artist_track(contributor));
             }
+            contributors.push(parse_contributor(contributor));
         }
     }
 
+    let composers = contributors
+        .iter()
+        .filter(|c| c.role.eq_ignore_ascii_case("Composer"))
+        .map(|c| c.name.clone())
+        .collect();
+
     // Parse album
-    let album = json
-        .get("album")
-        .map(|a| parse_album_track(a))
-        .unwrap_or_default();
+    let album = json.get("album").map(parse_album_track).unwrap_or_default();
 
     // Get track/disc position
     let track_number = json
@@ -235,9 +301,89 @@ pub fn parse_track(json: &Value) -> Result<Track> {
                 .map(|s| s.to_string()),
             ..Default::default()
         },
+        // The public API reports 0 when the BPM is unknown
+        bpm: get_f64(json, "bpm").filter(|&bpm| bpm > 0.0),
+        gain: get_f64(json, "gain"),
+        readable: json.get("readable").and_then(|v| v.as_bool()),
+        fallback_id: None,
+        disc_subtitle: None,
+        composers,
+        contributors,
     })
 }
 
+/// Merge gateway song data (`song.getData` results) into a track.
+///
+/// The gateway is authoritative for BPM, gain, availability, disc number,
+/// disc subtitle, composers and fallback. Its release date and label are only used when
+/// the public album data has none; everything else is left as parsed from
+/// the public API.
+pub fn merge_song_data(track: &mut Track, song: &Value) {
+    if let Some(bpm) = get_f64(song, "BPM").filter(|&bpm| bpm > 0.0) {
+        track.bpm = Some(bpm);
+    }
+
+    if let Some(gain) = get_f64(song, "GAIN") {
+        track.gain = Some(gain);
+    }
+
+    if let Some(md5_origin) = song.get("MD5_ORIGIN").and_then(|v| v.as_str()) {
+        track.readable = Some(!md5_origin.is_empty());
+    }
+
+    if let Some(disc) = get_id(song, "DISK_NUMBER").and_then(|d| d.parse::<u32>().ok()) {
+        if disc > 0 {
+            track.disc_number = disc;
+            track.album.total_discs = track.album.total_discs.max(disc);
+        }
+    }
+
+    if let Some(subtitle) = song
+        .get("DISK_TITLE")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        track.disc_subtitle = Some(subtitle.to_string());
+    }
+
+    // SNG_CONTRIBUTORS maps roles to names, e.g. {"composer": ["..."]}
+    let composers: Vec<String> = song
+        .get("SNG_CONTRIBUTORS")
+        .and_then(|c| c.get("composer"))
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.as_str())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    if !composers.is_empty() {
+        track.composers = composers;
+    }
+
+    if track.album.label.is_none() {
+        track.album.label = get_label(song, "LABEL_NAME");
+    }
+
+    if track.album.release_date.year <= 0 {
+        let gateway_date = ["DIGITAL_RELEASE_DATE", "PHYSICAL_RELEASE_DATE"]
+            .iter()
+            .filter_map(|key| song.get(*key).and_then(|v| v.as_str()))
+            .map(parse_release_date)
+            .find(|date| date.year > 0);
+
+        if let Some(date) = gateway_date {
+            track.album.release_date = date;
+        }
+    }
+
+    if let Some(fallback) = song.get("FALLBACK").and_then(|f| get_id(f, "SNG_ID")) {
+        track.fallback_id = Some(fallback);
+    }
+}
+
 /// Parse an album from raw JSON.
 pub fn parse_album(json: &Value) -> Result<Album> {
     let id = get_id(json, "id");
@@ -347,7 +493,7 @@ pub fn parse_album(json: &Value) -> Result<Album> {
         total_discs,
         genres: extract_genres(json),
         images: extract_images(json),
-        copyrights: Vec::new(), // Deezer API doesn't provide this in the same way
+        copyrights: Vec::new(), // Only the gateway has them, see merge_album_copyrights
         ids: IDs {
             deezer: id,
             upc: json
@@ -358,6 +504,7 @@ pub fn parse_album(json: &Value) -> Result<Album> {
         },
         tracks,
         artists,
+        label: get_label(json, "label"),
     })
 }
 
@@ -517,7 +664,259 @@ pub fn parse_artist(json: &Value) -> Result<Artist> {
         genres: Vec::new(), // Artist genres aren't typically available from basic endpoint
         images: extract_images(json),
         ids: IDs::with_deezer(id.unwrap_or_default()),
-        albums: Vec::new(), // Would need separate API call for discography
+        albums: Vec::new(), // Filled in by DeezerApi::get_artist_with_albums
+        bio: None,
+        fans: json.get("nb_fan").and_then(|v| v.as_u64()),
+    })
+}
+
+/// Merge gateway artist page data (`deezer.pageArtist` results) into an artist.
+///
+/// Adds the biography and takes the gateway's fan count when present.
+pub fn merge_artist_page(artist: &mut Artist, page: &Value) {
+    let bio = page.get("BIO").unwrap_or(&Value::Null);
+    let text = ["BIO", "RESUME"]
+        .iter()
+        .filter_map(|key| bio.get(key).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .find(|text| !text.is_empty());
+    if let Some(text) = text {
+        artist.bio = Some(text.to_string());
+    }
+
+    let data = page.get("DATA").unwrap_or(&Value::Null);
+    if let Some(fans) = get_id(data, "NB_FAN").and_then(|n| n.parse().ok()) {
+        artist.fans = Some(fans);
+    }
+}
+
+/// Merge the copyright lines of a gateway song object into its album.
+///
+/// Reads the `COPYRIGHT` and `PRODUCER_LINE` fields, e.g. from the `DATA`
+/// of a `deezer.pageTrack` response. Lines starting with `℗` or `(P)` are
+/// phonogram (P-line) copyrights, the others C-line copyrights. Existing
+/// copyrights are kept; duplicates are skipped.
+pub fn merge_album_copyrights(album: &mut Album, song: &Value) {
+    for key in ["COPYRIGHT", "PRODUCER_LINE"] {
+        let text = get_str(song, key);
+        let text = text.trim();
+        if text.is_empty() || album.copyrights.iter().any(|c| c.text == text) {
+            continue;
+        }
+
+        let is_phonogram = text.starts_with('℗')
+            || text
+                .get(..3)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("(p)"));
+
+        album.copyrights.push(Copyright {
+            text: text.to_string(),
+            type_: if is_phonogram { "P" } else { "C" }.to_string(),
+        });
+    }
+}
+
+/// Parse a playlist from a gateway playlist object.
+///
+/// Gateway list methods only carry the playlist header, so `tracks` is empty.
+pub fn parse_gateway_playlist(json: &Value) -> Result<Playlist> {
+    let id = get_id(json, "PLAYLIST_ID")
+        .ok_or_else(|| DeezerError::ApiError("Missing playlist ID".to_string()))?;
+
+    Ok(Playlist {
+        type_: "playlist".to_string(),
+        title: get_str(json, "TITLE"),
+        description: json
+            .get("DESCRIPTION")
+            .and_then(|d| d.as_str())
+            .filter(|d| !d.is_empty())
+            .map(|d| d.to_string()),
+        owner: User {
+            name: get_str(json, "PARENT_USERNAME"),
+            ids: IDs {
+                deezer: get_id(json, "PARENT_USER_ID"),
+                ..Default::default()
+            },
+        },
+        tracks: Vec::new(),
+        images: Vec::new(),
+        ids: IDs::with_deezer(id),
+    })
+}
+
+/// Parse the track list of a gateway `song.getListByAlbum` response.
+///
+/// Unlike the public track list, this can include region-filtered tracks.
+pub fn parse_gateway_album_tracks(json: &Value) -> Vec<TrackAlbum> {
+    let parse_num = |song: &Value, key: &str| get_id(song, key).and_then(|v| v.parse::<u64>().ok());
+
+    json.get("data")
+        .and_then(|d| d.as_array())
+        .map(|songs| {
+            songs
+                .iter()
+                .filter_map(|song| {
+                    let id = get_id(song, "SNG_ID")?;
+
+                    let artists = song
+                        .get("ARTISTS")
+                        .and_then(|a| a.as_array())
+                        .map(|artists| {
+                            artists
+                                .iter()
+                                .map(|artist| ArtistTrackAlbum {
+                                    type_: "artistTrackAlbum".to_string(),
+                                    name: get_str(artist, "ART_NAME"),
+                                    ids: IDs::with_deezer(
+                                        get_id(artist, "ART_ID").unwrap_or_default(),
+                                    ),
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
+                    Some(TrackAlbum {
+                        type_: "trackAlbum".to_string(),
+                        title: get_str(song, "SNG_TITLE"),
+                        disc_number: parse_num(song, "DISK_NUMBER").unwrap_or(1) as u32,
+                        track_number: parse_num(song, "TRACK_NUMBER").unwrap_or(0) as u32,
+                        duration_ms: parse_num(song, "DURATION").unwrap_or(0) * 1000,
+                        explicit: parse_num(song, "EXPLICIT_LYRICS") == Some(1),
+                        genres: Vec::new(),
+                        ids: IDs {
+                            deezer: Some(id),
+                            isrc: song
+                                .get("ISRC")
+                                .and_then(|v| v.as_str())
+                                .filter(|s| !s.is_empty())
+                                .map(|s| s.to_string()),
+                            ..Default::default()
+                        },
+                        artists,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the track list of a gateway `playlist.getSongs` response.
+///
+/// Positions follow the order of the response. Repeated tracks are kept,
+/// since a playlist may contain the same track more than once.
+pub fn parse_gateway_playlist_tracks(json: &Value) -> Vec<TrackPlaylist> {
+    let parse_num = |song: &Value, key: &str| get_id(song, key).and_then(|v| v.parse::<u64>().ok());
+
+    let Some(songs) = json.get("data").and_then(|d| d.as_array()) else {
+        return Vec::new();
+    };
+
+    songs
+        .iter()
+        .enumerate()
+        .filter_map(|(position, song)| {
+            let id = get_id(song, "SNG_ID")?;
+
+            let mut artists: Vec<ArtistTrackPlaylist> = song
+                .get("ARTISTS")
+                .and_then(|a| a.as_array())
+                .map(|artists| {
+                    artists
+                        .iter()
+                        .map(|artist| ArtistTrackPlaylist {
+                            type_: "artistTrackPlaylist".to_string(),
+                            name: get_str(artist, "ART_NAME"),
+                            ids: IDs::with_deezer(get_id(artist, "ART_ID").unwrap_or_default()),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            if artists.is_empty() && song.get("ART_NAME").is_some() {
+                artists.push(ArtistTrackPlaylist {
+                    type_: "artistTrackPlaylist".to_string(),
+                    name: get_str(song, "ART_NAME"),
+                    ids: IDs::with_deezer(get_id(song, "ART_ID").unwrap_or_default()),
+                });
+            }
+
+            let images = song
+                .get("ALB_PICTURE")
+                .and_then(|p| p.as_str())
+                .filter(|p| !p.is_empty())
+                .map(|md5| {
+                    [56, 250, 500, 1000]
+                        .into_iter()
+                        .map(|size| {
+                            let url = cover_image_url(md5, &format!("{0}x{0}", size));
+                            Image::new(url, size, size)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let album = AlbumTrackPlaylist {
+                type_: "albumTrackPlaylist".to_string(),
+                title: get_str(song, "ALB_TITLE"),
+                ids: IDs::with_deezer(get_id(song, "ALB_ID").unwrap_or_default()),
+                images,
+                artists: vec![ArtistAlbumTrackPlaylist {
+                    type_: "artistAlbumTrackPlaylist".to_string(),
+                    name: get_str(song, "ART_NAME"),
+                    ids: IDs::with_deezer(get_id(song, "ART_ID").unwrap_or_default()),
+                }],
+                album_type: String::new(),
+                release_date: parse_release_date(
+                    song.get("PHYSICAL_RELEASE_DATE")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or(""),
+                ),
+                total_tracks: 0,
+                total_discs: 1,
+            };
+
+            Some(TrackPlaylist {
+                type_: "trackPlaylist".to_string(),
+                title: get_str(song, "SNG_TITLE"),
+                position: position as u32,
+                duration_ms: parse_num(song, "DURATION").unwrap_or(0) * 1000,
+                artists,
+                album,
+                ids: IDs {
+                    deezer: Some(id),
+                    isrc: song
+                        .get("ISRC")
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.to_string()),
+                    ..Default::default()
+                },
+                disc_number: parse_num(song, "DISK_NUMBER").unwrap_or(1) as u32,
+                track_number: parse_num(song, "TRACK_NUMBER").unwrap_or(0) as u32,
+                explicit: parse_num(song, "EXPLICIT_LYRICS") == Some(1),
+            })
+        })
+        .collect()
+}
+
+/// Parse an album in artist context (`artist/{id}/albums` entries).
+pub fn parse_artist_album(json: &Value) -> Result<ArtistAlbum> {
+    let id = get_id(json, "id");
+    if id.is_none() {
+        return Err(DeezerError::ApiError("Missing album ID".to_string()));
+    }
+
+    Ok(ArtistAlbum {
+        type_: "albumArtist".to_string(),
+        album_type: get_str(json, "record_type"),
+        title: get_str(json, "title"),
+        release_date: parse_release_date(
+            json.get("release_date")
+                .and_then(|d| d.as_str())
+                .unwrap_or(""),
+        ),
+        total_tracks: get_u32(json, "nb_tracks"),
+        ids: IDs::with_deezer(id.unwrap_or_default()),
+        images: extract_images(json),
     })
 }
 
@@ -573,6 +972,223 @@ mod tests {
         assert_eq!(track.track_number, 1);
         assert_eq!(track.artists[0].name, "Test Artist");
         assert_eq!(track.album.title, "Test Album");
+        assert_eq!(track.album.track_artist_count, 0);
+        assert!(track.contributors.is_empty());
+    }
+
+    #[test]
+    fn test_parse_track_contributors() {
+        let json = json!({
+            "id": 69693501,
+            "title": "Get Lucky",
+            "artist": {"id": 27, "name": "Daft Punk"},
+            "contributors": [
+                {"id": 27, "name": "Daft Punk", "role": "Main"},
+                {"id": 103, "name": "Pharrell Williams", "role": "Featured"},
+                {"id": 5, "name": "Nile Rodgers", "role": "Composer"}
+            ]
+        });
+
+        let track = parse_track(&json).unwrap();
+        assert_eq!(track.artists.len(), 3);
+        assert_eq!(track.contributors.len(), 3);
+        assert_eq!(
+            track.contributors[1],
+            Contributor::new("Pharrell Williams", "Featured", "103")
+        );
+        assert_eq!(track.composers, vec!["Nile Rodgers"]);
+    }
+
+    #[test]
+    fn test_count_track_artists() {
+        let album = json!({
+            "tracks": {"data": [
+                {"artist": {"id": 1, "name": "A"}},
+                {"artist": {"id": 2, "name": "B"}},
+                {"artist": {"id": 1, "name": "A"}},
+                {"artist": {"name": "C"}},
+                {}
+            ]}
+        });
+        assert_eq!(count_track_artists(&album), 3);
+        assert_eq!(count_track_artists(&json!({})), 0);
+    }
+
+    #[test]
+    fn test_merge_song_data() {
+        let mut track = parse_track(&json!({
+            "id": 12345,
+            "title": "Test Track",
+            "bpm": 0,
+            "gain": -9.1
+        }))
+        .unwrap();
+        assert_eq!(track.bpm, None);
+
+        let song = json!({
+            "SNG_ID": "12345",
+            "BPM": "124.5",
+            "GAIN": "-8.2",
+            "MD5_ORIGIN": "abcdef",
+            "DISK_NUMBER": "2",
+            "DISK_TITLE": "The Studio Album",
+            "DIGITAL_RELEASE_DATE": "0000-00-00",
+            "PHYSICAL_RELEASE_DATE": "2019-11-22",
+            "LABEL_NAME": "Parlophone",
+            "SNG_CONTRIBUTORS": {
+                "main_artist": ["Radiohead"],
+                "composer": ["Thom Yorke", "Jonny Greenwood", ""]
+            },
+            "FALLBACK": {"SNG_ID": "67890"}
+        });
+        merge_song_data(&mut track, &song);
+
+        assert_eq!(track.bpm, Some(124.5));
+        assert_eq!(track.gain, Some(-8.2));
+        assert_eq!(track.readable, Some(true));
+        assert_eq!(track.disc_number, 2);
+        assert_eq!(track.album.total_discs, 2);
+        assert_eq!(track.disc_subtitle, Some("The Studio Album".to_string()));
+        assert_eq!(track.fallback_id, Some("67890".to_string()));
+        assert_eq!(track.album.release_date.year, 2019);
+        assert_eq!(track.album.label, Some("Parlophone".to_string()));
+        assert_eq!(track.composers, vec!["Thom Yorke", "Jonny Greenwood"]);
+
+        // A public release date is kept
+        merge_song_data(&mut track, &json!({"DIGITAL_RELEASE_DATE": "2021-01-01"}));
+        assert_eq!(track.album.release_date.year, 2019);
+    }
+
+    #[test]
+    fn test_merge_artist_page() {
+        let mut artist = parse_artist(&json!({
+            "id": 27,
+            "name": "Daft Punk",
+            "nb_fan": 4000000
+        }))
+        .unwrap();
+        assert_eq!(artist.fans, Some(4_000_000));
+
+        let page = json!({
+            "DATA": {"ART_ID": "27", "NB_FAN": 4500000},
+            "BIO": {"BIO": "", "RESUME": " French electronic duo. "}
+        });
+        merge_artist_page(&mut artist, &page);
+
+        assert_eq!(artist.bio, Some("French electronic duo.".to_string()));
+        assert_eq!(artist.fans, Some(4_500_000));
+    }
+
+    #[test]
+    fn test_merge_album_copyrights() {
+        let mut album = Album::default();
+        let song = json!({
+            "SNG_ID": "3135556",
+            "COPYRIGHT": "(C) 2001 Daft Life Ltd.",
+            "PRODUCER_LINE": "℗ 2001 Daft Life Ltd."
+        });
+        merge_album_copyrights(&mut album, &song);
+        merge_album_copyrights(&mut album, &song);
+
+        assert_eq!(album.copyrights.len(), 2);
+        assert_eq!(album.copyrights[0].type_, "C");
+        assert_eq!(album.copyrights[0].text, "(C) 2001 Daft Life Ltd.");
+        assert_eq!(album.copyrights[1].type_, "P");
+
+        let mut album = Album::default();
+        merge_album_copyrights(&mut album, &json!({"COPYRIGHT": "(P) 2013 Columbia"}));
+        assert_eq!(album.copyrights[0].type_, "P");
+    }
+
+    #[test]
+    fn test_parse_gateway_playlist() {
+        let json = json!({
+            "PLAYLIST_ID": "908622995",
+            "TITLE": "Electro Hits",
+            "PARENT_USERNAME": "Deezer Electro",
+            "PARENT_USER_ID": "2834392844"
+        });
+
+        let playlist = parse_gateway_playlist(&json).unwrap();
+        assert_eq!(playlist.title, "Electro Hits");
+        assert_eq!(playlist.deezer_id(), Some("908622995"));
+        assert_eq!(playlist.owner.name, "Deezer Electro");
+        assert!(playlist.tracks.is_empty());
+    }
+
+    #[test]
+    fn test_parse_gateway_album_tracks() {
+        let json = json!({
+            "data": [
+                {
+                    "SNG_ID": "3135556",
+                    "SNG_TITLE": "Harder, Better, Faster, Stronger",
+                    "DISK_NUMBER": "1",
+                    "TRACK_NUMBER": "4",
+                    "DURATION": "224",
+                    "EXPLICIT_LYRICS": "0",
+                    "ISRC": "GBDUW0000059",
+                    "ARTISTS": [{"ART_ID": "27", "ART_NAME": "Daft Punk"}]
+                },
+                {"SNG_TITLE": "No ID"}
+            ],
+            "count": 2,
+            "total": 2
+        });
+
+        let tracks = parse_gateway_album_tracks(&json);
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].track_number, 4);
+        assert_eq!(tracks[0].duration_ms, 224_000);
+        assert!(!tracks[0].explicit);
+        assert_eq!(tracks[0].ids.isrc, Some("GBDUW0000059".to_string()));
+        assert_eq!(tracks[0].primary_artist(), Some("Daft Punk"));
+    }
+
+    #[test]
+    fn test_parse_gateway_playlist_tracks() {
+        let song = json!({
+            "SNG_ID": "3135556",
+            "SNG_TITLE": "Harder, Better, Faster, Stronger",
+            "DURATION": "224",
+            "ART_ID": "27",
+            "ART_NAME": "Daft Punk",
+            "ALB_ID": "302127",
+            "ALB_TITLE": "Discovery",
+            "ALB_PICTURE": "2e018122cb56986277102d2041a592c8"
+        });
+        let json = json!({
+            "data": [song.clone(), {"SNG_TITLE": "No ID"}, song],
+            "count": 3,
+            "total": 3
+        });
+
+        let tracks = parse_gateway_playlist_tracks(&json);
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].position, 0);
+        assert_eq!(tracks[1].position, 2);
+        assert_eq!(tracks[0].ids, tracks[1].ids);
+        assert_eq!(tracks[0].duration_ms, 224_000);
+        assert_eq!(tracks[0].artists[0].name, "Daft Punk");
+        assert_eq!(tracks[0].album.title, "Discovery");
+        assert_eq!(tracks[0].album.images.len(), 4);
+        assert!(tracks[0].album.images[3].url.contains("/1000x1000-"));
+    }
+
+    #[test]
+    fn test_parse_artist_album() {
+        let json = json!({
+            "id": 302127,
+            "title": "Discovery",
+            "record_type": "album",
+            "release_date": "2001-03-07",
+            "cover_xl": "https://e-cdns-images.dzcdn.net/images/cover/abc/1000x1000-000000-80-0-0.jpg"
+        });
+
+        let album = parse_artist_album(&json).unwrap();
+        assert_eq!(album.title, "Discovery");
+        assert_eq!(album.ids.deezer, Some("302127".to_string()));
+        assert_eq!(album.images.len(), 1);
     }
 
     #[test]
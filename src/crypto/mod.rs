@@ -31,6 +31,116 @@ const BLOCK_SIZE: usize = 2048;
 /// Blowfish cipher block size.
 const BF_BLOCK_SIZE: usize = 8;
 
+/// Cipher name of Deezer's Blowfish stripe scheme.
+pub const CIPHER_BF_CBC_STRIPE: &str = "BF_CBC_STRIPE";
+
+/// Cipher name of unencrypted media.
+pub const CIPHER_NONE: &str = "NONE";
+
+/// Parameters of the stripe encryption scheme.
+///
+/// The defaults match Deezer's scheme: 2048-byte blocks, every 3rd block
+/// encrypted with Blowfish CBC using a fixed IV. Other values are mainly
+/// useful for testing with synthetic data; build them with
+/// [`StripeConfig::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StripeConfig {
+    /// Every `interval`-th block (starting with block 0) is encrypted.
+    ///
+    /// An interval of 0 means no block is encrypted.
+    interval: usize,
+    /// Size of each block in bytes, a non-zero multiple of 8.
+    block_size: usize,
+    /// Blowfish CBC initialization vector.
+    iv: [u8; BF_BLOCK_SIZE],
+}
+
+impl Default for StripeConfig {
+    fn default() -> Self {
+        Self {
+            interval: 3,
+            block_size: BLOCK_SIZE,
+            iv: BLOWFISH_IV,
+        }
+    }
+}
+
+impl StripeConfig {
+    /// Create stripe parameters.
+    ///
+    /// Every `interval`-th block of `block_size` bytes (starting with block
+    /// 0) is decrypted with Blowfish CBC using `iv`; an interval of 0 means
+    /// no block is encrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptoError` if `block_size` is 0 or not a multiple of the
+    /// 8-byte Blowfish block.
+    pub fn new(interval: usize, block_size: usize, iv: [u8; BF_BLOCK_SIZE]) -> Result<Self> {
+        let config = Self {
+            interval,
+            block_size,
+            iv,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Get the interval between encrypted blocks (0 for none).
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// Get the block size in bytes.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Get the Blowfish CBC initialization vector.
+    pub fn iv(&self) -> [u8; BF_BLOCK_SIZE] {
+        self.iv
+    }
+
+    /// Check that the block size can be decrypted block by block.
+    fn validate(&self) -> Result<()> {
+        if self.block_size == 0 || !self.block_size.is_multiple_of(BF_BLOCK_SIZE) {
+            return Err(DeezerError::CryptoError(format!(
+                "Invalid stripe block size: {} (expected a non-zero multiple of {})",
+                self.block_size, BF_BLOCK_SIZE
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check whether the block at `index` is encrypted.
+    pub fn is_encrypted_block(&self, index: usize) -> bool {
+        self.interval != 0 && index.is_multiple_of(self.interval)
+    }
+
+    /// Get the stripe parameters for a media cipher reported by Deezer.
+    ///
+    /// `BF_CBC_STRIPE` uses the default scheme and `NONE` passes every
+    /// block through untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptoError` for ciphers that are not stripe based. AES
+    /// media needs its key and nonce, see [`decrypt_file`].
+    pub fn for_cipher(cipher: &str) -> Result<Self> {
+        match cipher {
+            CIPHER_BF_CBC_STRIPE => Ok(Self::default()),
+            CIPHER_NONE => Ok(Self {
+                interval: 0,
+                ..Self::default()
+            }),
+            other => Err(DeezerError::CryptoError(format!(
+                "Unsupported media cipher: {}",
+                other
+            ))),
+        }
+    }
+}
+
 /// Compute MD5 hash of a string and return as hex string.
 pub fn md5_hex(data: &str) -> String {
     let mut hasher = Md5::new();
@@ -66,32 +176,44 @@ pub fn gen_song_hash(song_id: &str, song_md5: &str, media_version: &str) -> Stri
 /// - First 16 chars of MD5(song_id)
 /// - Second 16 chars of MD5(song_id)
 /// - Deezer's secret key
-pub fn calc_blowfish_key(song_id: &str) -> Vec<u8> {
+///
+/// # Errors
+///
+/// Returns `CryptoError` if `song_id` is not a Deezer song ID: digits,
+/// optionally preceded by `-` (used for user uploads).
+pub fn calc_blowfish_key(song_id: &str) -> Result<[u8; 16]> {
+    let digits = song_id.strip_prefix('-').unwrap_or(song_id);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(DeezerError::CryptoError(format!(
+            "Invalid song ID for key derivation: {:?}",
+            song_id
+        )));
+    }
+
     let hash = md5_hex(song_id);
     let hash_bytes = hash.as_bytes();
 
     debug!("MD5 hash of song ID '{}': {}", song_id, hash);
 
-    let mut key = Vec::with_capacity(16);
-    for i in 0..16 {
-        let byte = hash_bytes[i] ^ hash_bytes[i + 16] ^ SECRET_KEY[i];
-        key.push(byte);
+    let mut key = [0u8; 16];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = hash_bytes[i] ^ hash_bytes[i + 16] ^ SECRET_KEY[i];
     }
 
-    debug!("Generated Blowfish key: {}", hex::encode(&key));
-    key
+    debug!("Generated Blowfish key: {}", hex::encode(key));
+    Ok(key)
 }
 
 /// Decrypt a chunk using Blowfish CBC mode.
 ///
 /// This is a simplified implementation using the blowfish crate.
-fn decrypt_blowfish_cbc(data: &[u8], key: &[u8]) -> Vec<u8> {
+fn decrypt_blowfish_cbc(data: &[u8], key: &[u8], iv: &[u8; BF_BLOCK_SIZE]) -> Vec<u8> {
     use blowfish::Blowfish;
     use cipher::generic_array::GenericArray;
     use cipher::BlockDecrypt;
     use cipher::KeyInit;
 
-    if data.len() % BF_BLOCK_SIZE != 0 {
+    if !data.len().is_multiple_of(BF_BLOCK_SIZE) {
         warn!(
             "Data length {} is not a multiple of {} bytes",
             data.len(),
@@ -104,7 +226,7 @@ fn decrypt_blowfish_cbc(data: &[u8], key: &[u8]) -> Vec<u8> {
         Blowfish::new_from_slice(key).expect("Invalid key length for Blowfish");
 
     let mut result = data.to_vec();
-    let mut prev_block = BLOWFISH_IV.to_vec();
+    let mut prev_block = iv.to_vec();
 
     // Decrypt each 8-byte block with CBC mode
     for chunk in result.chunks_mut(BF_BLOCK_SIZE) {
@@ -136,7 +258,7 @@ fn decrypt_blowfish_cbc(data: &[u8], key: &[u8]) -> Vec<u8> {
 /// 1. Creates a fresh Blowfish cipher with the IV
 /// 2. Decrypts the entire block in CBC mode
 pub fn decrypt_blowfish_chunk(data: &[u8], key: &[u8]) -> Vec<u8> {
-    decrypt_blowfish_cbc(data, key)
+    decrypt_blowfish_cbc(data, key, &BLOWFISH_IV)
 }
 
 /// Decrypt a Deezer audio track.
@@ -152,38 +274,205 @@ pub fn decrypt_blowfish_chunk(data: &[u8], key: &[u8]) -> Vec<u8> {
 /// * `song_id` - The song ID for key derivation
 /// * `output_path` - Path to write the decrypted file
 pub fn decrypt_track(encrypted_data: &[u8], song_id: &str, output_path: &Path) -> Result<()> {
-    let key = calc_blowfish_key(song_id);
+    debug!("Decrypting track {} to {:?}", song_id, output_path);
+
+    let decrypted = decrypt_track_buffer(encrypted_data, song_id)?;
+    File::create(output_path)?.write_all(&decrypted)?;
+
+    Ok(())
+}
+
+/// Decrypt a Deezer audio track in memory, without any file I/O.
+///
+/// Applies the same stripe scheme as [`decrypt_track`]: every 3rd
+/// 2048-byte block is decrypted with Blowfish CBC, the other blocks and a
+/// trailing partial block are passed through untouched.
+///
+/// # Errors
+///
+/// Returns `CryptoError` if `song_id` is invalid, see [`calc_blowfish_key`].
+pub fn decrypt_track_buffer(encrypted: &[u8], song_id: &str) -> Result<Vec<u8>> {
+    decrypt_track_to_vec_with_config(encrypted, song_id, &StripeConfig::default())
+}
+
+/// Decrypt a Deezer audio track encrypted with the given media cipher.
+///
+/// # Arguments
+///
+/// * `encrypted_data` - The encrypted audio data
+/// * `cipher` - Cipher reported with the media URL (e.g. "BF_CBC_STRIPE")
+/// * `song_id` - The Deezer song ID (used for key derivation)
+/// * `output_path` - Path to write the decrypted file
+pub fn decrypt_media(
+    encrypted_data: &[u8],
+    cipher: &str,
+    song_id: &str,
+    output_path: &Path,
+) -> Result<()> {
+    let config = StripeConfig::for_cipher(cipher)?;
+    decrypt_track_with_config(encrypted_data, song_id, output_path, &config)
+}
+
+/// Decrypt a Deezer audio track using custom stripe parameters.
+///
+/// Same as [`decrypt_track`], but with the block size, interval and IV
+/// taken from `config`.
+pub fn decrypt_track_with_config(
+    encrypted_data: &[u8],
+    song_id: &str,
+    output_path: &Path,
+    config: &StripeConfig,
+) -> Result<()> {
+    debug!("Decrypting track {} to {:?}", song_id, output_path);
+
+    let decrypted = decrypt_track_to_vec_with_config(encrypted_data, song_id, config)?;
+    File::create(output_path)?.write_all(&decrypted)?;
+
+    Ok(())
+}
+
+/// Decrypt a Deezer audio track into memory.
+///
+/// Same as [`decrypt_track_buffer`], which replaces it.
+#[deprecated(note = "use `decrypt_track_buffer` instead")]
+pub fn decrypt_track_to_vec(encrypted_data: &[u8], song_id: &str) -> Result<Vec<u8>> {
+    decrypt_track_buffer(encrypted_data, song_id)
+}
+
+/// Decrypt a Deezer audio track encrypted with the given media cipher into memory.
+///
+/// Same as [`decrypt_media`], but returns the decrypted bytes instead of
+/// writing them to a file.
+pub fn decrypt_media_to_vec(encrypted_data: &[u8], cipher: &str, song_id: &str) -> Result<Vec<u8>> {
+    let config = StripeConfig::for_cipher(cipher)?;
+    decrypt_track_to_vec_with_config(encrypted_data, song_id, &config)
+}
+
+/// Decrypt a Deezer audio track into memory using custom stripe parameters.
+///
+/// # Errors
+///
+/// Returns `CryptoError` if `song_id` or the block size is invalid.
+pub fn decrypt_track_to_vec_with_config(
+    encrypted_data: &[u8],
+    song_id: &str,
+    config: &StripeConfig,
+) -> Result<Vec<u8>> {
+    config.validate()?;
+    let key = calc_blowfish_key(song_id)?;
 
     debug!(
-        "Decrypting track {} ({} bytes) to {:?}",
+        "Decrypting track {} ({} bytes)",
         song_id,
-        encrypted_data.len(),
-        output_path
+        encrypted_data.len()
     );
 
-    let mut output = File::create(output_path)?;
+    let mut output = Vec::with_capacity(encrypted_data.len());
     let mut block_count = 0;
 
-    for chunk in encrypted_data.chunks(BLOCK_SIZE) {
-        let processed = if block_count % 3 == 0 && chunk.len() == BLOCK_SIZE {
+    for chunk in encrypted_data.chunks(config.block_size) {
+        if config.is_encrypted_block(block_count) && chunk.len() == config.block_size {
             // Decrypt this block
             debug!("Decrypting block {} (size: {})", block_count, chunk.len());
-            decrypt_blowfish_chunk(chunk, &key)
+            output.extend_from_slice(&decrypt_blowfish_cbc(chunk, &key, &config.iv));
         } else {
             // Pass through unencrypted
-            chunk.to_vec()
-        };
-
-        output.write_all(&processed)?;
+            output.extend_from_slice(chunk);
+        }
         block_count += 1;
     }
 
-    debug!(
-        "Successfully decrypted {} blocks to {:?}",
-        block_count, output_path
-    );
+    debug!("Successfully decrypted {} blocks", block_count);
 
-    Ok(())
+    Ok(output)
+}
+
+/// Incremental decryptor for stripe-encrypted media.
+///
+/// Takes the encrypted stream in chunks of any size and keeps the block
+/// index and any partial block between calls, so a track can be decrypted
+/// while it downloads. At most one block is buffered at a time.
+#[derive(Debug, Clone)]
+pub struct StripeDecryptor {
+    key: [u8; 16],
+    config: StripeConfig,
+    buffer: Vec<u8>,
+    block_index: usize,
+}
+
+impl StripeDecryptor {
+    /// Create a decryptor for `song_id` using the given stripe parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptoError` if `song_id` is invalid, see
+    /// [`calc_blowfish_key`], or if the block size is invalid, see
+    /// [`StripeConfig::new`].
+    pub fn new(song_id: &str, config: StripeConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            key: calc_blowfish_key(song_id)?,
+            buffer: Vec::with_capacity(config.block_size),
+            config,
+            block_index: 0,
+        })
+    }
+
+    /// Create a decryptor for media encrypted with the given cipher.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptoError` for ciphers that are not stripe based, see
+    /// [`StripeConfig::for_cipher`], and for invalid song IDs.
+    pub fn for_cipher(cipher: &str, song_id: &str) -> Result<Self> {
+        Self::new(song_id, StripeConfig::for_cipher(cipher)?)
+    }
+
+    /// Feed the next chunk of encrypted data.
+    ///
+    /// Returns the decrypted bytes of every block completed by this chunk,
+    /// which may be empty.
+    pub fn update(&mut self, mut data: &[u8]) -> Vec<u8> {
+        let block_size = self.config.block_size;
+        let mut output = Vec::with_capacity(self.buffer.len() + data.len());
+
+        while !data.is_empty() {
+            let wanted = block_size - self.buffer.len();
+            let (head, rest) = data.split_at(wanted.min(data.len()));
+            data = rest;
+
+            // Decrypt straight from the input when a whole block is there
+            let block = if self.buffer.is_empty() && head.len() == block_size {
+                head
+            } else {
+                self.buffer.extend_from_slice(head);
+                if self.buffer.len() < block_size {
+                    break;
+                }
+                &self.buffer
+            };
+
+            if self.config.is_encrypted_block(self.block_index) {
+                output.extend_from_slice(&decrypt_blowfish_cbc(block, &self.key, &self.config.iv));
+            } else {
+                output.extend_from_slice(block);
+            }
+            self.buffer.clear();
+            self.block_index += 1;
+        }
+
+        output
+    }
+
+    /// Finish decryption, returning the trailing partial block.
+    ///
+    /// A partial block is never encrypted, so it is returned as is.
+    pub fn finish(self) -> Vec<u8> {
+        if !self.buffer.is_empty() {
+            debug!("Final partial block of {} bytes", self.buffer.len());
+        }
+        self.buffer
+    }
 }
 
 /// Decrypt a Deezer audio track from a reader (streaming).
@@ -200,12 +489,10 @@ pub fn decrypt_track_streaming<R: Read>(
     song_id: &str,
     output_path: &Path,
 ) -> Result<()> {
-    let key = calc_blowfish_key(song_id);
+    let mut decryptor = StripeDecryptor::new(song_id, StripeConfig::default())?;
 
     let mut output = File::create(output_path)?;
     let mut buffer = [0u8; BLOCK_SIZE];
-    let mut block_count = 0;
-    let mut accumulated = Vec::new();
 
     loop {
         let bytes_read = reader.read(&mut buffer)?;
@@ -213,28 +500,10 @@ pub fn decrypt_track_streaming<R: Read>(
             break;
         }
 
-        accumulated.extend_from_slice(&buffer[..bytes_read]);
-
-        // Process complete blocks
-        while accumulated.len() >= BLOCK_SIZE {
-            let block: Vec<u8> = accumulated.drain(..BLOCK_SIZE).collect();
-
-            let processed = if block_count % 3 == 0 {
-                decrypt_blowfish_chunk(&block, &key)
-            } else {
-                block
-            };
-
-            output.write_all(&processed)?;
-            block_count += 1;
-        }
+        output.write_all(&decryptor.update(&buffer[..bytes_read]))?;
     }
 
-    // Write any remaining data (partial block, not encrypted)
-    if !accumulated.is_empty() {
-        debug!("Writing final partial block of {} bytes", accumulated.len());
-        output.write_all(&accumulated)?;
-    }
+    output.write_all(&decryptor.finish())?;
 
     Ok(())
 }
@@ -243,9 +512,21 @@ pub fn decrypt_track_streaming<R: Read>(
 ///
 /// This is used for newer Deezer content.
 pub fn decrypt_aes_ctr(data: &[u8], key: &[u8], nonce: &[u8]) -> Result<Vec<u8>> {
-    use aes::Aes128;
-    use ctr::cipher::{KeyIvInit, StreamCipher};
-    use ctr::Ctr128BE;
+    use ctr::cipher::StreamCipher;
+
+    let mut cipher = aes_ctr_cipher(key, nonce)?;
+    let mut result = data.to_vec();
+    cipher.apply_keystream(&mut result);
+
+    Ok(result)
+}
+
+/// AES-128 in CTR mode with a big-endian counter, as used by Deezer.
+type AesCtr = ctr::Ctr128BE<aes::Aes128>;
+
+/// Create an AES-CTR cipher, checking the key and nonce lengths.
+fn aes_ctr_cipher(key: &[u8], nonce: &[u8]) -> Result<AesCtr> {
+    use ctr::cipher::KeyIvInit;
 
     if key.len() != 16 {
         return Err(DeezerError::CryptoError(format!(
@@ -261,19 +542,14 @@ pub fn decrypt_aes_ctr(data: &[u8], key: &[u8], nonce: &[u8]) -> Result<Vec<u8>>
         )));
     }
 
-    let mut cipher = Ctr128BE::<Aes128>::new_from_slices(key, nonce)
-        .map_err(|e| DeezerError::CryptoError(format!("Failed to create AES cipher: {}", e)))?;
-
-    let mut result = data.to_vec();
-    cipher.apply_keystream(&mut result);
-
-    Ok(result)
+    AesCtr::new_from_slices(key, nonce)
+        .map_err(|e| DeezerError::CryptoError(format!("Failed to create AES cipher: {}", e)))
 }
 
 /// Encryption parameters for a track.
 #[derive(Debug, Clone)]
 pub struct EncryptionParams {
-    /// Type of encryption: "blowfish" or "aes"
+    /// Type of encryption: "blowfish", "aes" or "none"
     pub encryption_type: String,
     /// Track ID (for Blowfish key derivation)
     pub track_id: String,
@@ -285,40 +561,168 @@ pub struct EncryptionParams {
     pub nonce: Option<String>,
 }
 
+impl EncryptionParams {
+    /// Decode the hex AES key and nonce.
+    fn aes_key_nonce(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| DeezerError::CryptoError("Missing AES key".to_string()))?;
+        let nonce = self
+            .nonce
+            .as_ref()
+            .ok_or_else(|| DeezerError::CryptoError("Missing AES nonce".to_string()))?;
+
+        let key_bytes = hex::decode(key)
+            .map_err(|e| DeezerError::CryptoError(format!("Invalid key hex: {}", e)))?;
+        let nonce_bytes = hex::decode(nonce)
+            .map_err(|e| DeezerError::CryptoError(format!("Invalid nonce hex: {}", e)))?;
+
+        Ok((key_bytes, nonce_bytes))
+    }
+}
+
 /// Decrypt a file using the appropriate method based on parameters.
 pub fn decrypt_file(
     encrypted_data: &[u8],
     params: &EncryptionParams,
     output_path: &Path,
 ) -> Result<()> {
+    let decrypted = decrypt_to_vec(encrypted_data, params)?;
+    File::create(output_path)?.write_all(&decrypted)?;
+
+    debug!("Successfully decrypted file to {:?}", output_path);
+    Ok(())
+}
+
+/// Decrypt media into memory using the appropriate method based on parameters.
+///
+/// Same as [`decrypt_file`], but returns the decrypted bytes instead of
+/// writing them to a file. Media of type "none" is returned as is.
+pub fn decrypt_to_vec(encrypted_data: &[u8], params: &EncryptionParams) -> Result<Vec<u8>> {
     match params.encryption_type.as_str() {
         "aes" => {
-            let key = params
-                .key
-                .as_ref()
-                .ok_or_else(|| DeezerError::CryptoError("Missing AES key".to_string()))?;
-            let nonce = params
-                .nonce
-                .as_ref()
-                .ok_or_else(|| DeezerError::CryptoError("Missing AES nonce".to_string()))?;
-
-            let key_bytes = hex::decode(key)
-                .map_err(|e| DeezerError::CryptoError(format!("Invalid key hex: {}", e)))?;
-            let nonce_bytes = hex::decode(nonce)
-                .map_err(|e| DeezerError::CryptoError(format!("Invalid nonce hex: {}", e)))?;
-
-            let decrypted = decrypt_aes_ctr(encrypted_data, &key_bytes, &nonce_bytes)?;
-
-            let mut output = File::create(output_path)?;
-            output.write_all(&decrypted)?;
-
-            debug!("Successfully decrypted AES file to {:?}", output_path);
-            Ok(())
+            let (key, nonce) = params.aes_key_nonce()?;
+            decrypt_aes_ctr(encrypted_data, &key, &nonce)
+        }
+        "none" => Ok(encrypted_data.to_vec()),
+        _ => decrypt_track_buffer(encrypted_data, &params.track_id),
+    }
+}
+
+/// Incremental decryptor for any media Deezer serves.
+///
+/// Dispatches on [`EncryptionParams::encryption_type`] like
+/// [`decrypt_file`], but takes the encrypted stream in chunks so a track
+/// can be decrypted while it downloads.
+pub enum MediaDecryptor {
+    /// Blowfish stripe media.
+    Stripe(StripeDecryptor),
+    /// AES-CTR media.
+    Aes(Box<AesCtr>),
+    /// Unencrypted media, passed through as is.
+    Passthrough,
+}
+
+impl MediaDecryptor {
+    /// Create a decryptor for media described by `params`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CryptoError` if the AES key or nonce is missing or invalid,
+    /// or if the track ID is invalid for stripe media. Unencrypted media
+    /// needs neither.
+    pub fn new(params: &EncryptionParams) -> Result<Self> {
+        match params.encryption_type.as_str() {
+            "aes" => {
+                let (key, nonce) = params.aes_key_nonce()?;
+                Ok(Self::Aes(Box::new(aes_ctr_cipher(&key, &nonce)?)))
+            }
+            "none" => Ok(Self::Passthrough),
+            _ => Ok(Self::Stripe(StripeDecryptor::new(
+                &params.track_id,
+                StripeConfig::default(),
+            )?)),
+        }
+    }
+
+    /// Feed the next chunk of encrypted data.
+    ///
+    /// Returns the decrypted bytes available so far, which may be empty.
+    pub fn update(&mut self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Stripe(decryptor) => decryptor.update(data),
+            Self::Aes(cipher) => {
+                use ctr::cipher::StreamCipher;
+
+                let mut output = data.to_vec();
+                cipher.apply_keystream(&mut output);
+                output
+            }
+            Self::Passthrough => data.to_vec(),
+        }
+    }
+
+    /// Finish decryption, returning any buffered trailing bytes.
+    pub fn finish(self) -> Vec<u8> {
+        match self {
+            Self::Stripe(decryptor) => decryptor.finish(),
+            Self::Aes(_) | Self::Passthrough => Vec::new(),
         }
-        "blowfish" | _ => decrypt_track(encrypted_data, &params.track_id, output_path),
     }
 }
 
+impl std::fmt::Debug for MediaDecryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stripe(decryptor) => f.debug_tuple("Stripe").field(decryptor).finish(),
+            Self::Aes(_) => f.debug_tuple("Aes").finish_non_exhaustive(),
+            Self::Passthrough => f.write_str("Passthrough"),
+        }
+    }
+}
+
+/// Audio container format detected from decrypted bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    /// FLAC lossless.
+    Flac,
+    /// MPEG audio (MP3), with or without an ID3 tag.
+    Mp3,
+}
+
+impl AudioFormat {
+    /// Get the file extension for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Flac => ".flac",
+            AudioFormat::Mp3 => ".mp3",
+        }
+    }
+}
+
+/// Detect the audio format of decrypted bytes from their header.
+///
+/// Recognizes the `fLaC` signature, ID3v2 tags and MPEG audio frame sync.
+/// Returns `None` if the header is not recognized, which usually means
+/// decryption went wrong.
+pub fn detect_audio_format(bytes: &[u8]) -> Option<AudioFormat> {
+    if bytes.starts_with(b"fLaC") {
+        return Some(AudioFormat::Flac);
+    }
+
+    if bytes.starts_with(b"ID3") {
+        return Some(AudioFormat::Mp3);
+    }
+
+    // MPEG frame sync: 11 set bits, and a layer other than "reserved"
+    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] & 0xE0 == 0xE0 && bytes[1] & 0x06 != 0 {
+        return Some(AudioFormat::Mp3);
+    }
+
+    None
+}
+
 /// Analyze a FLAC file for structure validation.
 ///
 /// This is useful for debugging decryption issues.
@@ -416,6 +820,44 @@ pub struct FlacAnalysis {
     pub potential_issues: Vec<String>,
 }
 
+impl FlacAnalysis {
+    /// Check whether the first metadata block is STREAMINFO, as FLAC requires.
+    pub fn has_streaminfo(&self) -> bool {
+        self.metadata_blocks
+            .first()
+            .is_some_and(|block| block.block_type == 0)
+    }
+}
+
+/// Check that an MP3 file starts with audio, after its ID3v2 tag if any.
+///
+/// Looks for MPEG frame sync right at the start of the file, or right
+/// after the ID3v2 tag. A cheap counterpart to [`analyze_flac_file`] for
+/// catching garbage output from a failed decryption.
+pub fn has_mp3_audio(file_path: &Path) -> Result<bool> {
+    use std::io::{Seek, SeekFrom};
+
+    let mut file = File::open(file_path)?;
+    let mut header = [0u8; 10];
+    if file.read_exact(&mut header).is_err() {
+        return Ok(false);
+    }
+
+    if header.starts_with(b"ID3") {
+        // Syncsafe tag size, plus the header and an optional footer
+        let size = header[6..10]
+            .iter()
+            .fold(0u64, |size, &byte| (size << 7) | (byte & 0x7F) as u64);
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        file.seek(SeekFrom::Start(10 + size + footer))?;
+        if file.read_exact(&mut header[..2]).is_err() {
+            return Ok(false);
+        }
+    }
+
+    Ok(header[0] == 0xFF && header[1] & 0xE0 == 0xE0 && header[1] & 0x06 != 0)
+}
+
 /// FLAC metadata block info.
 #[derive(Debug)]
 pub struct MetadataBlock {
@@ -440,11 +882,18 @@ mod tests {
     #[test]
     fn test_calc_blowfish_key() {
         // Test with a known song ID
-        let key = calc_blowfish_key("3135556");
-        assert_eq!(key.len(), 16);
+        let key = calc_blowfish_key("3135556").unwrap();
         // The key should be deterministic
-        let key2 = calc_blowfish_key("3135556");
+        let key2 = calc_blowfish_key("3135556").unwrap();
         assert_eq!(key, key2);
+
+        // User uploads have negative IDs
+        assert!(calc_blowfish_key("-1234567").is_ok());
+
+        assert!(calc_blowfish_key("").is_err());
+        assert!(calc_blowfish_key("-").is_err());
+        assert!(calc_blowfish_key("test_song_id").is_err());
+        assert!(calc_blowfish_key("3135556\u{e9}").is_err());
     }
 
     #[test]
@@ -454,6 +903,85 @@ mod tests {
         assert_eq!(hash.len(), 40); // SHA1 produces 40 hex chars
     }
 
+    #[test]
+    fn test_detect_audio_format() {
+        assert_eq!(
+            detect_audio_format(b"fLaC\x00\x00\x00\x22"),
+            Some(AudioFormat::Flac)
+        );
+        assert_eq!(detect_audio_format(b"ID3\x04\x00"), Some(AudioFormat::Mp3));
+        assert_eq!(
+            detect_audio_format(&[0xFF, 0xFB, 0x90, 0x00]),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(detect_audio_format(&[0x00, 0x01, 0x02, 0x03]), None);
+        assert_eq!(detect_audio_format(&[]), None);
+    }
+
+    #[test]
+    fn test_stripe_config_interval() {
+        let config = StripeConfig::default();
+        assert!(config.is_encrypted_block(0));
+        assert!(!config.is_encrypted_block(1));
+        assert!(config.is_encrypted_block(3));
+
+        let none = StripeConfig::new(0, BLOCK_SIZE, BLOWFISH_IV).unwrap();
+        assert!(!none.is_encrypted_block(0));
+    }
+
+    #[test]
+    fn test_stripe_config_rejects_invalid_block_size() {
+        assert!(StripeConfig::new(3, 0, BLOWFISH_IV).is_err());
+        assert!(StripeConfig::new(3, 12, BLOWFISH_IV).is_err());
+        assert_eq!(
+            StripeConfig::new(3, 16, BLOWFISH_IV).unwrap().block_size(),
+            16
+        );
+
+        // Configs built around the constructor are rejected by both paths
+        for block_size in [0, 12] {
+            let invalid = StripeConfig {
+                block_size,
+                ..Default::default()
+            };
+            assert!(decrypt_track_to_vec_with_config(&[0u8; 64], "3135556", &invalid).is_err());
+            assert!(StripeDecryptor::new("3135556", invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_stripe_config_for_cipher() {
+        assert_eq!(
+            StripeConfig::for_cipher("BF_CBC_STRIPE").unwrap(),
+            StripeConfig::default()
+        );
+        assert_eq!(StripeConfig::for_cipher("NONE").unwrap().interval(), 0);
+        assert!(StripeConfig::for_cipher("AES_CTR").is_err());
+    }
+
+    #[test]
+    fn test_decrypt_with_custom_stripe_config() {
+        let original: Vec<u8> = (0..64).map(|i| i as u8).collect();
+        let temp_dir = std::env::temp_dir();
+
+        // With interval 0 nothing is decrypted, so data passes through untouched
+        let passthrough = StripeConfig::new(0, 16, BLOWFISH_IV).unwrap();
+        let path = temp_dir.join("test_stripe_passthrough.bin");
+        decrypt_track_with_config(&original, "3135556", &path, &passthrough).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), original);
+
+        // With interval 2, only blocks 0 and 2 are touched
+        let every_other = StripeConfig::new(2, 16, BLOWFISH_IV).unwrap();
+        decrypt_track_with_config(&original, "3135556", &path, &every_other).unwrap();
+        let output = std::fs::read(&path).unwrap();
+        assert_ne!(output[..16], original[..16]);
+        assert_eq!(output[16..32], original[16..32]);
+        assert_ne!(output[32..48], original[32..48]);
+        assert_eq!(output[48..], original[48..]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn test_encryption_decryption_roundtrip() {
         // This is a simplified test - real Deezer audio has specific structure
@@ -463,10 +991,150 @@ mod tests {
         let temp_dir = std::env::temp_dir();
         let output_path = temp_dir.join("test_decrypt.bin");
 
-        let result = decrypt_track(&original, "test_song_id", &output_path);
+        let result = decrypt_track(&original, "3135556", &output_path);
         assert!(result.is_ok());
 
         // Cleanup
         let _ = std::fs::remove_file(&output_path);
     }
+
+    #[test]
+    fn test_decrypt_track_buffer_round_trip() {
+        use blowfish::Blowfish;
+        use cipher::generic_array::GenericArray;
+        use cipher::{BlockEncrypt, KeyInit};
+
+        // Three full blocks and a partial one
+        let original: Vec<u8> = (0..BLOCK_SIZE * 3 + 100).map(|i| (i % 251) as u8).collect();
+
+        // Encrypt block 0 like Deezer does: Blowfish CBC with the fixed IV
+        let key = calc_blowfish_key("3135556").unwrap();
+        let cipher: Blowfish<byteorder::BE> = Blowfish::new_from_slice(&key).unwrap();
+        let mut encrypted = original.clone();
+        let mut prev = BLOWFISH_IV;
+        for chunk in encrypted[..BLOCK_SIZE].chunks_mut(BF_BLOCK_SIZE) {
+            for (byte, p) in chunk.iter_mut().zip(prev.iter()) {
+                *byte ^= p;
+            }
+            cipher.encrypt_block(GenericArray::from_mut_slice(chunk));
+            prev.copy_from_slice(chunk);
+        }
+        assert_ne!(encrypted[..BLOCK_SIZE], original[..BLOCK_SIZE]);
+
+        let decrypted = decrypt_track_buffer(&encrypted, "3135556").unwrap();
+        assert_eq!(decrypted, original);
+
+        assert!(decrypt_track_buffer(&encrypted, "").is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_decrypt_track_to_vec_matches_file() {
+        let original: Vec<u8> = (0..BLOCK_SIZE * 4).map(|i| i as u8).collect();
+        let path = std::env::temp_dir().join("test_decrypt_to_vec.bin");
+
+        decrypt_track(&original, "3135556", &path).unwrap();
+        let in_memory = decrypt_track_to_vec(&original, "3135556").unwrap();
+        assert_eq!(in_memory, std::fs::read(&path).unwrap());
+        assert_eq!(
+            in_memory,
+            decrypt_track_buffer(&original, "3135556").unwrap()
+        );
+        assert_eq!(in_memory.len(), original.len());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_has_mp3_audio() {
+        let path = std::env::temp_dir().join("test_has_mp3_audio.mp3");
+
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x64, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert!(has_mp3_audio(&path).unwrap());
+
+        // A 4-byte ID3v2 tag followed by a frame
+        let mut tagged = b"ID3\x04\x00\x00\x00\x00\x00\x04".to_vec();
+        tagged.extend_from_slice(&[0, 0, 0, 0, 0xFF, 0xFB, 0x90, 0x64]);
+        std::fs::write(&path, &tagged).unwrap();
+        assert!(has_mp3_audio(&path).unwrap());
+
+        // A tag followed by garbage
+        tagged[14] = 0x12;
+        std::fs::write(&path, &tagged).unwrap();
+        assert!(!has_mp3_audio(&path).unwrap());
+
+        std::fs::write(&path, b"fLaC").unwrap();
+        assert!(!has_mp3_audio(&path).unwrap());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_stripe_decryptor_chunked() {
+        let original: Vec<u8> = (0..BLOCK_SIZE * 5 + 700).map(|i| (i * 7) as u8).collect();
+        let expected = decrypt_track_buffer(&original, "3135556").unwrap();
+
+        // Chunk sizes that straddle block boundaries in different ways
+        for chunk_size in [1, 777, BLOCK_SIZE, BLOCK_SIZE * 3 + 5] {
+            let mut decryptor = StripeDecryptor::new("3135556", StripeConfig::default()).unwrap();
+            let mut output = Vec::new();
+            for chunk in original.chunks(chunk_size) {
+                output.extend(decryptor.update(chunk));
+            }
+            output.extend(decryptor.finish());
+            assert_eq!(output, expected, "chunk size {}", chunk_size);
+        }
+    }
+
+    #[test]
+    fn test_media_decryptor_dispatch() {
+        let original: Vec<u8> = (0..BLOCK_SIZE * 3 + 100).map(|i| (i * 7) as u8).collect();
+        let params = |encryption_type: &str| EncryptionParams {
+            encryption_type: encryption_type.to_string(),
+            track_id: "3135556".to_string(),
+            md5_origin: None,
+            key: Some("000102030405060708090a0b0c0d0e0f".to_string()),
+            nonce: Some("0f0e0d0c0b0a09080706050403020100".to_string()),
+        };
+
+        for encryption_type in ["blowfish", "aes", "none"] {
+            let params = params(encryption_type);
+            let expected = decrypt_to_vec(&original, &params).unwrap();
+
+            let mut decryptor = MediaDecryptor::new(&params).unwrap();
+            let mut output = Vec::new();
+            for chunk in original.chunks(777) {
+                output.extend(decryptor.update(chunk));
+            }
+            output.extend(decryptor.finish());
+            assert_eq!(output, expected, "{}", encryption_type);
+        }
+
+        assert_eq!(
+            decrypt_to_vec(&original, &params("none")).unwrap(),
+            original
+        );
+        assert_ne!(decrypt_to_vec(&original, &params("aes")).unwrap(), original);
+    }
+
+    #[test]
+    fn test_media_decryptor_none_without_track_id() {
+        let original: Vec<u8> = (0..BLOCK_SIZE * 2 + 10).map(|i| i as u8).collect();
+        let params = EncryptionParams {
+            encryption_type: "none".to_string(),
+            track_id: String::new(),
+            md5_origin: None,
+            key: None,
+            nonce: None,
+        };
+
+        let mut decryptor = MediaDecryptor::new(&params).unwrap();
+        let mut output = Vec::new();
+        for chunk in original.chunks(1000) {
+            output.extend(decryptor.update(chunk));
+        }
+        output.extend(decryptor.finish());
+        assert_eq!(output, original);
+        assert_eq!(decrypt_to_vec(&original, &params).unwrap(), original);
+    }
 }
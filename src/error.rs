@@ -25,10 +25,23 @@ pub enum DeezerError {
     #[error("Bad credentials: {0}")]
     BadCredentials(String),
 
+    /// The account is locked or suspended and cannot be used.
+    #[error("Account locked: {0}")]
+    AccountLocked(String),
+
+    /// The account needs re-verification (e.g. 2FA or a new login).
+    #[error("Re-authentication required: {0}")]
+    ReauthRequired(String),
+
     /// No rights to access the media (premium required).
     #[error("No rights on media: {0}")]
     NoRightOnMedia(String),
 
+    /// The content can't be downloaded as a file (live streams, tracks
+    /// without a duration).
+    #[error("Streaming-only/unsupported: {0}")]
+    UnsupportedContent(String),
+
     /// Requested quality is not available.
     #[error("Quality not available: {0}")]
     QualityNotFound(String),
@@ -41,6 +54,10 @@ pub enum DeezerError {
     #[error("Invalid link: {0}")]
     InvalidLink(String),
 
+    /// Invalid or unsupported proxy URL.
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
+
     /// No data returned from API.
     #[error("No data from API: {0}")]
     NoDataApi(String),
@@ -46,17 +46,29 @@
 //! - [`crypto`] - Decryption utilities
 
 pub mod api;
+pub mod cache;
 pub mod converters;
 pub mod crypto;
 pub mod error;
+pub mod link;
+pub mod manifest;
 pub mod models;
 mod rusteer;
 pub mod tagging;
+mod throttle;
 
 // Main interface (recommended)
-pub use rusteer::{BatchDownloadResult, DownloadQuality, DownloadResult, Rusteer};
+pub use rusteer::{
+    supported_audio_extensions, BatchDownloadResult, BatchProgress, ContentType,
+    CoverDownloadResult, DownloadEvent, DownloadProgress, DownloadQuality, DownloadResult,
+    GenreSource, OrphanTagging, PlannedDownload, PlaylistLayout, ProgressCallback, Rusteer,
+    TrackCompleteCallback,
+};
 
 // Low-level APIs
-pub use api::{DeezerApi, GatewayApi};
+pub use api::{DeezerApi, GatewayApi, SearchPage};
 pub use error::DeezerError;
-pub use models::{Album, Artist, Playlist, Track};
+pub use link::{
+    is_short_link, parse_deezer_url, resolve_deezer_url, validate_deezer_id, DeezerLink,
+};
+pub use models::{Album, Artist, ArtistStyle, Playlist, Track};
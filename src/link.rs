@@ -0,0 +1,244 @@
+//! Deezer link parsing.
+//!
+//! This module turns deezer.com URLs into typed references to the
+//! content they point at.
+
+use crate::error::{DeezerError, Result};
+
+/// Hosts of Deezer share links that redirect to a deezer.com URL.
+const SHORT_LINK_HOSTS: [&str; 2] = ["link.deezer.com", "deezer.page.link"];
+
+/// A reference to Deezer content parsed from a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeezerLink {
+    /// A single track.
+    Track(String),
+    /// An album.
+    Album(String),
+    /// A playlist.
+    Playlist(String),
+    /// An artist.
+    Artist(String),
+    /// A single podcast episode.
+    Episode(String),
+    /// A podcast show.
+    Show(String),
+}
+
+impl DeezerLink {
+    /// Get the Deezer ID of the linked content.
+    pub fn id(&self) -> &str {
+        match self {
+            DeezerLink::Track(id)
+            | DeezerLink::Album(id)
+            | DeezerLink::Playlist(id)
+            | DeezerLink::Artist(id)
+            | DeezerLink::Episode(id)
+            | DeezerLink::Show(id) => id,
+        }
+    }
+}
+
+/// Parse a deezer.com URL into a [`DeezerLink`].
+///
+/// Recognizes `/track/`, `/album/`, `/playlist/`, `/artist/`, `/episode/`
+/// and `/show/` paths on `deezer.com` or one of its subdomains. Locale
+/// prefixes (e.g. `/en/`), trailing slashes, query strings and fragments
+/// are ignored.
+///
+/// # Errors
+///
+/// Returns `InvalidLink` if the URL is not a recognized Deezer link.
+pub fn parse_deezer_url(url: &str) -> Result<DeezerLink> {
+    let (host, rest) = split_host(url);
+    let host = host.to_ascii_lowercase();
+    if host != "deezer.com" && !host.ends_with(".deezer.com") {
+        return Err(DeezerError::InvalidLink(url.to_string()));
+    }
+
+    // Only the path names the content; drop query string and fragment
+    let path = rest.split(['?', '#']).next().unwrap_or(rest);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    for pair in segments.windows(2) {
+        let id = pair[1];
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+
+        let link = match pair[0] {
+            "track" => DeezerLink::Track(id.to_string()),
+            "album" => DeezerLink::Album(id.to_string()),
+            "playlist" => DeezerLink::Playlist(id.to_string()),
+            "artist" => DeezerLink::Artist(id.to_string()),
+            "episode" => DeezerLink::Episode(id.to_string()),
+            "show" => DeezerLink::Show(id.to_string()),
+            _ => continue,
+        };
+
+        return Ok(link);
+    }
+
+    Err(DeezerError::InvalidLink(url.to_string()))
+}
+
+/// Check whether `url` is a Deezer share link (e.g. `link.deezer.com/s/...`).
+///
+/// Share links don't contain the content ID; they must be resolved with
+/// [`resolve_deezer_url`] first.
+pub fn is_short_link(url: &str) -> bool {
+    let (host, _) = split_host(url);
+    SHORT_LINK_HOSTS
+        .iter()
+        .any(|h| host.eq_ignore_ascii_case(h))
+}
+
+/// Split a URL, with or without a scheme, into its host and the rest.
+///
+/// The rest starts at the first `/`, `?` or `#` after the host.
+fn split_host(url: &str) -> (&str, &str) {
+    let trimmed = url.trim();
+    let without_scheme = trimmed
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(trimmed);
+    let end = without_scheme
+        .find(['/', '?', '#'])
+        .unwrap_or(without_scheme.len());
+    without_scheme.split_at(end)
+}
+
+/// Parse a Deezer URL, following share links to their target first.
+///
+/// Regular deezer.com URLs are parsed without any network access, as with
+/// [`parse_deezer_url`]. Share links are requested with `client` and the
+/// URL they redirect to is parsed instead; links pasted without a scheme
+/// (`link.deezer.com/s/...`) are requested over `https://`.
+///
+/// # Errors
+///
+/// Returns `RequestError` if a share link can't be followed, and
+/// `InvalidLink` if the (resolved) URL is not a recognized Deezer link.
+pub async fn resolve_deezer_url(client: &reqwest::Client, url: &str) -> Result<DeezerLink> {
+    if !is_short_link(url) {
+        return parse_deezer_url(url);
+    }
+
+    let response = client.get(with_scheme(url)).send().await?;
+    parse_deezer_url(response.url().as_str())
+}
+
+/// Prefix `url` with `https://` unless it already has a scheme.
+fn with_scheme(url: &str) -> String {
+    let trimmed = url.trim();
+    if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    }
+}
+
+/// Validate a Deezer ID and return it without surrounding whitespace.
+///
+/// Deezer IDs are positive integers; anything else (an empty string, a
+/// URL fragment, a negative number) is rejected before it reaches the API.
+///
+/// # Errors
+///
+/// Returns `InvalidLink` if the ID is not a positive integer.
+pub fn validate_deezer_id(id: &str) -> Result<&str> {
+    let trimmed = id.trim();
+    let is_positive_integer = !trimmed.is_empty()
+        && trimmed.chars().all(|c| c.is_ascii_digit())
+        && trimmed.chars().any(|c| c != '0');
+
+    if is_positive_integer {
+        Ok(trimmed)
+    } else {
+        Err(DeezerError::InvalidLink(format!(
+            "Invalid Deezer ID: {}",
+            id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_track_url() {
+        let link = parse_deezer_url("https://www.deezer.com/track/3135556").unwrap();
+        assert_eq!(link, DeezerLink::Track("3135556".to_string()));
+    }
+
+    #[test]
+    fn test_parse_locale_and_query() {
+        let link = parse_deezer_url("https://www.deezer.com/en/album/302127/?utm=abc").unwrap();
+        assert_eq!(link, DeezerLink::Album("302127".to_string()));
+    }
+
+    #[test]
+    fn test_parse_podcast_urls() {
+        let episode = parse_deezer_url("https://www.deezer.com/fr/episode/123456").unwrap();
+        assert_eq!(episode, DeezerLink::Episode("123456".to_string()));
+
+        let show = parse_deezer_url("https://www.deezer.com/show/98765").unwrap();
+        assert_eq!(show, DeezerLink::Show("98765".to_string()));
+        assert_eq!(show.id(), "98765");
+    }
+
+    #[test]
+    fn test_validate_deezer_id() {
+        assert_eq!(validate_deezer_id("3135556").unwrap(), "3135556");
+        assert_eq!(validate_deezer_id(" 302127\n").unwrap(), "302127");
+
+        assert!(validate_deezer_id("").is_err());
+        assert!(validate_deezer_id("0").is_err());
+        assert!(validate_deezer_id("-12").is_err());
+        assert!(validate_deezer_id("track/3135556").is_err());
+        assert!(validate_deezer_id("12ab").is_err());
+    }
+
+    #[test]
+    fn test_is_short_link() {
+        assert!(is_short_link("https://link.deezer.com/s/30ABCdef"));
+        assert!(is_short_link("deezer.page.link/abc123"));
+        assert!(!is_short_link("https://www.deezer.com/track/3135556"));
+        assert!(!is_short_link("https://example.com/link.deezer.com"));
+    }
+
+    #[test]
+    fn test_parse_invalid_url() {
+        assert!(parse_deezer_url("https://example.com/track/1").is_err());
+        assert!(parse_deezer_url("https://www.deezer.com/en/").is_err());
+    }
+
+    #[test]
+    fn test_parse_url_requires_deezer_host() {
+        assert!(parse_deezer_url("https://evil.example/track/1?x=deezer.com").is_err());
+        assert!(parse_deezer_url("https://deezer.com.evil.example/track/1").is_err());
+        assert!(parse_deezer_url("https://notdeezer.com/track/1").is_err());
+        assert!(parse_deezer_url("https://track/1.deezer.com/").is_err());
+        assert_eq!(
+            parse_deezer_url("deezer.com/track/1").unwrap(),
+            DeezerLink::Track("1".to_string())
+        );
+        assert_eq!(
+            parse_deezer_url("https://WWW.Deezer.com/track/1").unwrap(),
+            DeezerLink::Track("1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_with_scheme() {
+        assert_eq!(
+            with_scheme(" link.deezer.com/s/30ABCdef "),
+            "https://link.deezer.com/s/30ABCdef"
+        );
+        assert_eq!(
+            with_scheme("http://deezer.page.link/abc123"),
+            "http://deezer.page.link/abc123"
+        );
+    }
+}
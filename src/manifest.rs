@@ -0,0 +1,144 @@
+//! Download manifests.
+//!
+//! A manifest is a machine-readable record of what a batch download wrote
+//! to disk: which Deezer track each file came from, the quality served and
+//! a checksum of the final (tagged) file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::rusteer::{BatchDownloadResult, DownloadQuality};
+
+/// File name of the manifest written into a batch's output directory.
+pub const MANIFEST_FILE_NAME: &str = "rusteer-manifest.json";
+
+/// Record of a finished batch download.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Manifest {
+    /// URL of the album or playlist that was downloaded.
+    pub source_url: String,
+    /// Unix timestamp (seconds) of when the manifest was written.
+    pub created_at: u64,
+    /// One entry per downloaded file.
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// A single downloaded file in a [`Manifest`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ManifestEntry {
+    /// Path of the file, relative to the manifest's directory.
+    pub path: PathBuf,
+    /// Deezer track ID.
+    pub track_id: String,
+    /// Quality that was actually served.
+    pub quality: DownloadQuality,
+    /// File size in bytes.
+    pub size: u64,
+    /// Hex-encoded MD5 of the file contents.
+    pub md5: String,
+    /// URL of the track on deezer.com.
+    pub source_url: String,
+}
+
+impl Manifest {
+    /// Build a manifest for the successful downloads of a batch.
+    ///
+    /// Reads every downloaded file to compute its checksum.
+    pub fn from_batch(result: &BatchDownloadResult, source_url: &str) -> Result<Self> {
+        let mut entries = Vec::with_capacity(result.successful.len());
+
+        for download in &result.successful {
+            let contents = fs::read(&download.path)?;
+            let path = download
+                .path
+                .strip_prefix(&result.directory)
+                .unwrap_or(&download.path)
+                .to_path_buf();
+
+            entries.push(ManifestEntry {
+                path,
+                track_id: download.track_id.clone(),
+                quality: download.quality,
+                size: contents.len() as u64,
+                md5: hex::encode(Md5::digest(&contents)),
+                source_url: format!("https://www.deezer.com/track/{}", download.track_id),
+            });
+        }
+
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Ok(Self {
+            source_url: source_url.to_string(),
+            created_at,
+            entries,
+        })
+    }
+
+    /// Write the manifest as [`MANIFEST_FILE_NAME`] inside `dir`.
+    ///
+    /// Returns the path of the written file.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Read a manifest previously written with [`save`](Self::save).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rusteer::DownloadResult;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let dir = std::env::temp_dir().join(format!("rusteer-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("01 - One.mp3");
+        fs::write(&file, b"abc").unwrap();
+
+        let mut batch = BatchDownloadResult {
+            directory: dir.clone(),
+            successful: Vec::new(),
+            failed: Vec::new(),
+            skipped: Vec::new(),
+            aborted: None,
+        };
+        batch.successful.push(DownloadResult {
+            path: file,
+            track_id: "3135556".to_string(),
+            quality: DownloadQuality::Mp3_320,
+            requested_quality: DownloadQuality::Mp3_320,
+            size: 3,
+            title: "One".to_string(),
+            artist: "Artist".to_string(),
+            isrc: None,
+            album: None,
+            duration_ms: 0,
+        });
+
+        let manifest = Manifest::from_batch(&batch, "https://www.deezer.com/album/302127").unwrap();
+        let entry = &manifest.entries[0];
+        assert_eq!(entry.path, PathBuf::from("01 - One.mp3"));
+        assert_eq!(entry.md5, "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(entry.source_url, "https://www.deezer.com/track/3135556");
+
+        let path = manifest.save(&dir).unwrap();
+        assert_eq!(Manifest::load(&path).unwrap(), manifest);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
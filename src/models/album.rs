@@ -4,8 +4,9 @@
 //! nested tracks and artist information.
 
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-use super::common::{IDs, Image, ReleaseDate};
+use super::common::{format_duration, IDs, Image, ReleaseDate};
 
 /// Artist when nested inside an album context.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -123,12 +124,19 @@ impl TrackAlbum {
         self.artists.first().map(|a| a.name.as_str())
     }
 
-    /// Get duration formatted as MM:SS.
+    /// Get the duration.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+
+    /// Get the duration in whole seconds.
+    pub fn duration_secs(&self) -> u64 {
+        self.duration_ms / 1000
+    }
+
+    /// Get duration formatted as MM:SS, or H:MM:SS from one hour on.
     pub fn duration_formatted(&self) -> String {
-        let total_seconds = self.duration_ms / 1000;
-        let minutes = total_seconds / 60;
-        let seconds = total_seconds % 60;
-        format!("{:02}:{:02}", minutes, seconds)
+        format_duration(self.duration_ms)
     }
 }
 
@@ -190,6 +198,10 @@ pub struct Album {
     /// Album artists.
     #[serde(default)]
     pub artists: Vec<AlbumArtist>,
+
+    /// Record label, if known.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 fn default_album_type() -> String {
@@ -216,6 +228,11 @@ impl Album {
         self.tracks.iter().map(|t| t.duration_ms).sum()
     }
 
+    /// Get total duration of all tracks formatted as MM:SS or H:MM:SS.
+    pub fn total_duration_formatted(&self) -> String {
+        format_duration(self.total_duration_ms())
+    }
+
     /// Get the Deezer album ID.
     pub fn deezer_id(&self) -> Option<&str> {
         self.ids.deezer.as_deref()
@@ -226,6 +243,21 @@ impl Album {
         self.images.iter().max_by_key(|img| img.width * img.height)
     }
 
+    /// Get how many tracks Deezer counts but the track list lacks.
+    ///
+    /// Region restrictions can filter tracks out of the public track list
+    /// while `nb_tracks` still counts them.
+    pub fn missing_tracks(&self) -> u32 {
+        self.total_tracks.saturating_sub(self.tracks.len() as u32)
+    }
+
+    /// Sort the track list by disc number, then track number.
+    ///
+    /// The sort is stable, so tracks with the same numbers keep their order.
+    pub fn sort_tracks(&mut self) {
+        self.tracks.sort_by_key(|t| (t.disc_number, t.track_number));
+    }
+
     /// Get tracks for a specific disc.
     pub fn tracks_for_disc(&self, disc_number: u32) -> Vec<&TrackAlbum> {
         self.tracks
@@ -255,6 +287,17 @@ mod tests {
             ..Default::default()
         };
         assert_eq!(album.total_duration_ms(), 420000);
+        assert_eq!(album.total_duration_formatted(), "07:00");
+    }
+
+    #[test]
+    fn test_track_album_duration_formatted() {
+        let track = TrackAlbum {
+            duration_ms: 4_500_000, // 1:15:00
+            ..Default::default()
+        };
+        assert_eq!(track.duration_formatted(), "1:15:00");
+        assert_eq!(track.duration_secs(), 4500);
     }
 
     #[test]
@@ -269,6 +312,37 @@ mod tests {
         assert_eq!(album.artists_string(" & "), "Artist A & Artist B");
     }
 
+    #[test]
+    fn test_album_missing_tracks() {
+        let mut album = Album {
+            total_tracks: 3,
+            tracks: vec![TrackAlbum::default(), TrackAlbum::default()],
+            ..Default::default()
+        };
+        assert_eq!(album.missing_tracks(), 1);
+
+        album.total_tracks = 1;
+        assert_eq!(album.missing_tracks(), 0);
+    }
+
+    #[test]
+    fn test_sort_tracks() {
+        let track = |title: &str, disc_number, track_number| TrackAlbum {
+            title: title.to_string(),
+            disc_number,
+            track_number,
+            ..Default::default()
+        };
+        let mut album = Album {
+            tracks: vec![track("2-1", 2, 1), track("1-2", 1, 2), track("1-1", 1, 1)],
+            ..Default::default()
+        };
+
+        album.sort_tracks();
+        let titles: Vec<_> = album.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["1-1", "1-2", "2-1"]);
+    }
+
     #[test]
     fn test_tracks_for_disc() {
         let album = Album {
@@ -28,6 +28,17 @@ pub struct AlbumArtist {
 
     /// Album identifiers.
     pub ids: IDs,
+
+    /// Cover images in various sizes.
+    #[serde(default)]
+    pub images: Vec<Image>,
+}
+
+impl AlbumArtist {
+    /// Get the largest cover image available.
+    pub fn largest_image(&self) -> Option<&Image> {
+        self.images.iter().max_by_key(|img| img.width * img.height)
+    }
 }
 
 fn default_album_artist_type() -> String {
@@ -72,6 +83,14 @@ pub struct Artist {
     /// Albums by this artist.
     #[serde(default)]
     pub albums: Vec<AlbumArtist>,
+
+    /// Artist biography, if available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bio: Option<String>,
+
+    /// Number of fans, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fans: Option<u64>,
 }
 
 fn default_artist_type() -> String {
@@ -102,7 +121,7 @@ impl Artist {
     /// Get all albums sorted by release date (newest first).
     pub fn albums_by_date(&self) -> Vec<&AlbumArtist> {
         let mut albums: Vec<_> = self.albums.iter().collect();
-        albums.sort_by(|a, b| b.release_date.year.cmp(&a.release_date.year));
+        albums.sort_by_key(|a| std::cmp::Reverse(a.release_date.year));
         albums
     }
 
@@ -190,4 +209,20 @@ mod tests {
         assert_eq!(sorted[0].title, "New Album");
         assert_eq!(sorted[1].title, "Old Album");
     }
+
+    #[test]
+    fn test_album_artist_largest_image() {
+        let album = AlbumArtist {
+            images: vec![
+                Image::new("https://cdn/1000x1000.jpg", 1000, 1000),
+                Image::new("https://cdn/56x56.jpg", 56, 56),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            album.largest_image().unwrap().url,
+            "https://cdn/1000x1000.jpg"
+        );
+        assert!(AlbumArtist::default().largest_image().is_none());
+    }
 }
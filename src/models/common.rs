@@ -75,13 +75,15 @@ impl ReleaseDate {
             day: parts.get(2).and_then(|s| s.parse().ok()),
         }
     }
+}
 
+impl std::fmt::Display for ReleaseDate {
     /// Format as "YYYY-MM-DD" string.
-    pub fn to_string(&self) -> String {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match (self.month, self.day) {
-            (Some(m), Some(d)) => format!("{:04}-{:02}-{:02}", self.year, m, d),
-            (Some(m), None) => format!("{:04}-{:02}", self.year, m),
-            _ => format!("{:04}", self.year),
+            (Some(m), Some(d)) => write!(f, "{:04}-{:02}-{:02}", self.year, m, d),
+            (Some(m), None) => write!(f, "{:04}-{:02}", self.year, m),
+            _ => write!(f, "{:04}", self.year),
         }
     }
 }
@@ -121,13 +123,14 @@ pub struct User {
 }
 
 /// Audio quality options.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Quality {
     /// MP3 128 kbps.
     #[serde(rename = "MP3_128")]
     Mp3_128,
     /// MP3 320 kbps.
     #[serde(rename = "MP3_320")]
+    #[default]
     Mp3_320,
     /// FLAC lossless.
     #[serde(rename = "FLAC")]
@@ -162,9 +165,17 @@ impl Quality {
     }
 }
 
-impl Default for Quality {
-    fn default() -> Self {
-        Self::Mp3_320
+/// Format a duration in milliseconds as `MM:SS`, or `H:MM:SS` from one hour on.
+pub fn format_duration(ms: u64) -> String {
+    let total_seconds = ms / 1000;
+    let hours = total_seconds / 3600;
+    let minutes = total_seconds % 3600 / 60;
+    let seconds = total_seconds % 60;
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
     }
 }
 
@@ -201,6 +212,12 @@ mod tests {
         assert_eq!(ids.isrc, None);
     }
 
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(215_000), "03:35");
+        assert_eq!(format_duration(4_335_900), "1:12:15");
+    }
+
     #[test]
     fn test_quality_code() {
         assert_eq!(Quality::Mp3_128.code(), "1");
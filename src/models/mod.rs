@@ -13,5 +13,5 @@ pub mod track;
 pub use album::{Album, AlbumArtist, TrackAlbum};
 pub use artist::{AlbumArtist as ArtistAlbum, Artist};
 pub use common::{IDs, Image, Quality, ReleaseDate};
-pub use playlist::{Playlist, TrackPlaylist};
-pub use track::{AlbumTrack, ArtistTrack, Track};
+pub use playlist::{Playlist, PlaylistDiff, TrackPlaylist};
+pub use track::{AlbumTrack, ArtistStyle, ArtistTrack, Contributor, Track};
@@ -4,8 +4,12 @@
 //! nested tracks.
 
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
 
-use super::common::{IDs, Image, ReleaseDate, User};
+use super::common::{format_duration, IDs, Image, ReleaseDate, User};
+use crate::error::Result;
 
 /// Artist when nested inside a track in a playlist context.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -167,12 +171,35 @@ impl TrackPlaylist {
             .join(separator)
     }
 
-    /// Get duration formatted as MM:SS.
+    /// Get the duration.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+
+    /// Get the duration in whole seconds.
+    pub fn duration_secs(&self) -> u64 {
+        self.duration_ms / 1000
+    }
+
+    /// Get duration formatted as MM:SS, or H:MM:SS from one hour on.
     pub fn duration_formatted(&self) -> String {
-        let total_seconds = self.duration_ms / 1000;
-        let minutes = total_seconds / 60;
-        let seconds = total_seconds % 60;
-        format!("{:02}:{:02}", minutes, seconds)
+        format_duration(self.duration_ms)
+    }
+}
+
+/// Tracks added and removed between two versions of a playlist.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistDiff {
+    /// Tracks only in the newer playlist.
+    pub added: Vec<TrackPlaylist>,
+    /// Tracks only in the older playlist.
+    pub removed: Vec<TrackPlaylist>,
+}
+
+impl PlaylistDiff {
+    /// Check whether the playlists contain the same tracks.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
     }
 }
 
@@ -220,6 +247,11 @@ impl Playlist {
         self.tracks.iter().map(|t| t.duration_ms).sum()
     }
 
+    /// Get total duration of all tracks formatted as MM:SS or H:MM:SS.
+    pub fn total_duration_formatted(&self) -> String {
+        format_duration(self.total_duration_ms())
+    }
+
     /// Get the number of tracks in the playlist.
     pub fn track_count(&self) -> usize {
         self.tracks.len()
@@ -229,6 +261,53 @@ impl Playlist {
     pub fn largest_image(&self) -> Option<&Image> {
         self.images.iter().max_by_key(|img| img.width * img.height)
     }
+
+    /// Save the playlist as a JSON snapshot.
+    ///
+    /// Load it back with [`load_snapshot`](Self::load_snapshot) and
+    /// [`diff`](Self::diff) it against a freshly fetched playlist to find
+    /// the tracks to download.
+    pub fn save_snapshot<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Load a playlist snapshot saved with [`save_snapshot`](Self::save_snapshot).
+    pub fn load_snapshot<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Compare this playlist with a newer version of it.
+    ///
+    /// Tracks are matched by ISRC or Deezer ID, so re-uploads of the
+    /// same recording count as the same track. Order changes are ignored.
+    pub fn diff(&self, newer: &Playlist) -> PlaylistDiff {
+        PlaylistDiff {
+            added: newer
+                .tracks
+                .iter()
+                .filter(|track| !self.contains_track(track))
+                .cloned()
+                .collect(),
+            removed: self
+                .tracks
+                .iter()
+                .filter(|track| !newer.contains_track(track))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Check whether the playlist contains a track with the same ISRC or Deezer ID.
+    fn contains_track(&self, track: &TrackPlaylist) -> bool {
+        let same = |a: &Option<String>, b: &Option<String>| a.is_some() && a == b;
+
+        self.tracks
+            .iter()
+            .any(|t| same(&t.ids.isrc, &track.ids.isrc) || same(&t.ids.deezer, &track.ids.deezer))
+    }
 }
 
 #[cfg(test)]
@@ -251,6 +330,22 @@ mod tests {
             ..Default::default()
         };
         assert_eq!(playlist.total_duration_ms(), 500000);
+        assert_eq!(playlist.total_duration_formatted(), "08:20");
+    }
+
+    #[test]
+    fn test_track_playlist_duration_formatted() {
+        let short = TrackPlaylist {
+            duration_ms: 3_599_000,
+            ..Default::default()
+        };
+        assert_eq!(short.duration_formatted(), "59:59");
+
+        let long = TrackPlaylist {
+            duration_ms: 3_600_000,
+            ..Default::default()
+        };
+        assert_eq!(long.duration_formatted(), "1:00:00");
     }
 
     #[test]
@@ -266,6 +361,51 @@ mod tests {
         assert_eq!(playlist.track_count(), 3);
     }
 
+    #[test]
+    fn test_playlist_diff() {
+        let track = |deezer: &str, isrc: &str| TrackPlaylist {
+            ids: IDs::with_deezer_and_isrc(deezer, isrc),
+            ..Default::default()
+        };
+
+        let old = Playlist {
+            tracks: vec![track("1", "AAA"), track("2", "BBB")],
+            ..Default::default()
+        };
+        let new = Playlist {
+            // "9" is a re-upload of "1" with the same ISRC
+            tracks: vec![track("9", "AAA"), track("3", "CCC")],
+            ..Default::default()
+        };
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec![track("3", "CCC")]);
+        assert_eq!(diff.removed, vec![track("2", "BBB")]);
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_playlist_snapshot_roundtrip() {
+        let playlist = Playlist {
+            title: "Road Trip".to_string(),
+            tracks: vec![TrackPlaylist {
+                title: "One More Time".to_string(),
+                position: 0,
+                ids: IDs::with_deezer_and_isrc("3135556", "GBDUW0000053"),
+                ..Default::default()
+            }],
+            ids: IDs::with_deezer("908622995"),
+            ..Default::default()
+        };
+
+        let path = std::env::temp_dir().join("rusteer_test_playlist_snapshot.json");
+        playlist.save_snapshot(&path).unwrap();
+        let loaded = Playlist::load_snapshot(&path).unwrap();
+        assert_eq!(loaded, playlist);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn test_track_playlist_artists_string() {
         let track = TrackPlaylist {
@@ -4,8 +4,10 @@
 //! nested artist/album information.
 
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-use super::common::{IDs, Image, ReleaseDate};
+use super::common::{format_duration, IDs, Image, ReleaseDate};
+use crate::tagging::AudioMetadata;
 
 /// Artist when nested inside a track context.
 ///
@@ -38,6 +40,54 @@ impl ArtistTrack {
     }
 }
 
+/// An artist credited on a track, with the role they are credited for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Contributor {
+    /// Contributor name.
+    pub name: String,
+
+    /// Credited role as reported by Deezer (e.g. `Main`, `Featured`).
+    #[serde(default)]
+    pub role: String,
+
+    /// Contributor identifiers.
+    pub ids: IDs,
+}
+
+impl Contributor {
+    /// Create a new contributor with name, role and Deezer ID.
+    pub fn new<S1, S2, S3>(name: S1, role: S2, deezer_id: S3) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            role: role.into(),
+            ids: IDs::with_deezer(deezer_id),
+        }
+    }
+}
+
+/// How the artists of a track are rendered into a single string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtistStyle {
+    /// Every artist, joined by the separator: `A, B, C`.
+    Join(String),
+    /// Main artists followed by a featuring clause: `A feat. B, C`.
+    ///
+    /// Relies on contributor roles; tracks without them are rendered as
+    /// with `Join(", ")`.
+    MainFeat,
+}
+
+impl Default for ArtistStyle {
+    fn default() -> Self {
+        ArtistStyle::Join(", ".to_string())
+    }
+}
+
 /// Artist when nested inside a track in an album context.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ArtistAlbumTrack {
@@ -106,6 +156,14 @@ pub struct AlbumTrack {
     /// Album artists.
     #[serde(default)]
     pub artists: Vec<ArtistAlbumTrack>,
+
+    /// Record label, if known.
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// Number of distinct primary artists among the album's tracks (0 if unknown).
+    #[serde(default)]
+    pub track_artist_count: u32,
 }
 
 fn default_album_track_type() -> String {
@@ -167,6 +225,34 @@ pub struct Track {
 
     /// Track identifiers.
     pub ids: IDs,
+
+    /// Beats per minute, if known.
+    #[serde(default)]
+    pub bpm: Option<f64>,
+
+    /// Replay gain in dB, if known.
+    #[serde(default)]
+    pub gain: Option<f64>,
+
+    /// Whether the track can be streamed, if known.
+    #[serde(default)]
+    pub readable: Option<bool>,
+
+    /// Deezer ID of the track served instead when this one is unavailable.
+    #[serde(default)]
+    pub fallback_id: Option<String>,
+
+    /// Title of the disc this track is on, for box sets that have one.
+    #[serde(default)]
+    pub disc_subtitle: Option<String>,
+
+    /// Composers credited on the track, if known.
+    #[serde(default)]
+    pub composers: Vec<String>,
+
+    /// Every artist credited on the track, with their roles.
+    #[serde(default)]
+    pub contributors: Vec<Contributor>,
 }
 
 fn default_track_type() -> String {
@@ -188,24 +274,178 @@ impl Track {
             .join(separator)
     }
 
-    /// Get duration formatted as MM:SS.
+    /// Get the artist names rendered in `style`.
+    pub fn artists_formatted(&self, style: &ArtistStyle) -> String {
+        let separator = match style {
+            ArtistStyle::Join(separator) => return self.artists_string(separator),
+            ArtistStyle::MainFeat => ", ",
+        };
+
+        let names = |role| {
+            self.contributors_with_role(role)
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        let main = names("Main");
+        if main.is_empty() {
+            return self.artists_string(separator);
+        }
+
+        let featured: Vec<_> = names("Featured")
+            .into_iter()
+            .filter(|name| !main.contains(name))
+            .collect();
+        if featured.is_empty() {
+            main.join(separator)
+        } else {
+            format!(
+                "{} feat. {}",
+                main.join(separator),
+                featured.join(separator)
+            )
+        }
+    }
+
+    /// Get the contributors credited with `role`, ignoring case.
+    pub fn contributors_with_role<'a>(
+        &'a self,
+        role: &'a str,
+    ) -> impl Iterator<Item = &'a Contributor> + 'a {
+        self.contributors
+            .iter()
+            .filter(move |c| c.role.eq_ignore_ascii_case(role))
+    }
+
+    /// Get the duration.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+
+    /// Get the duration in whole seconds.
+    pub fn duration_secs(&self) -> u64 {
+        self.duration_ms / 1000
+    }
+
+    /// Get duration formatted as MM:SS, or H:MM:SS from one hour on.
     pub fn duration_formatted(&self) -> String {
-        let total_seconds = self.duration_ms / 1000;
-        let minutes = total_seconds / 60;
-        let seconds = total_seconds % 60;
-        format!("{:02}:{:02}", minutes, seconds)
+        format_duration(self.duration_ms)
     }
 
     /// Get the Deezer track ID.
     pub fn deezer_id(&self) -> Option<&str> {
         self.ids.deezer.as_deref()
     }
+
+    /// Check whether the track came without usable album data.
+    ///
+    /// Some search results carry a near-empty album object; album-level
+    /// fields of such tracks are meaningless.
+    pub fn is_orphan(&self) -> bool {
+        self.album.title.trim().is_empty()
+    }
+
+    /// Build a partial track from tags read back from a file.
+    ///
+    /// Fills in what tags can carry: title, artists, album, numbering,
+    /// year, genres and ISRC. Joined artist and genre tags are split on
+    /// `", "`, as written by the downloader. Deezer IDs and the duration
+    /// are left empty, so the result is meant for looking the track up
+    /// again (e.g. by ISRC) rather than for downloading.
+    pub fn from_metadata(meta: &AudioMetadata) -> Track {
+        let album = AlbumTrack {
+            type_: default_album_track_type(),
+            title: meta.album.clone().unwrap_or_default(),
+            release_date: ReleaseDate {
+                year: meta.year.unwrap_or(0),
+                ..Default::default()
+            },
+            total_tracks: meta.total_tracks.unwrap_or(0),
+            total_discs: meta.total_discs.unwrap_or(1),
+            artists: split_tag(meta.album_artist.as_deref())
+                .map(|name| ArtistAlbumTrack {
+                    type_: default_artist_album_track_type(),
+                    name,
+                    ids: IDs::default(),
+                })
+                .collect(),
+            label: meta.label.clone(),
+            ..Default::default()
+        };
+
+        Track {
+            type_: default_track_type(),
+            title: meta.title.clone().unwrap_or_default(),
+            disc_number: meta.disc_number.unwrap_or(1),
+            track_number: meta.track_number.unwrap_or(1),
+            genres: split_tag(meta.genre.as_deref()).collect(),
+            album,
+            artists: split_tag(meta.artist.as_deref())
+                .map(|name| ArtistTrack {
+                    type_: default_artist_track_type(),
+                    name,
+                    ids: IDs::default(),
+                })
+                .collect(),
+            ids: IDs {
+                isrc: meta.isrc.clone(),
+                ..Default::default()
+            },
+            disc_subtitle: meta.disc_subtitle.clone(),
+            composers: split_tag(meta.composer.as_deref()).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Split a joined multi-value tag into its non-empty values.
+fn split_tag(value: Option<&str>) -> impl Iterator<Item = String> + '_ {
+    value
+        .unwrap_or_default()
+        .split(", ")
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_track_is_orphan() {
+        let mut track = Track::default();
+        assert!(track.is_orphan());
+
+        track.album.title = "Discovery".to_string();
+        assert!(!track.is_orphan());
+    }
+
+    #[test]
+    fn test_track_from_metadata() {
+        let meta = AudioMetadata::new()
+            .with_title("Get Lucky")
+            .with_artist("Daft Punk, Pharrell Williams")
+            .with_album("Random Access Memories")
+            .with_track(8, Some(13))
+            .with_year(2013)
+            .with_isrc("USQX91300108");
+
+        let track = Track::from_metadata(&meta);
+        assert_eq!(track.type_, "track");
+        assert_eq!(track.title, "Get Lucky");
+        assert_eq!(track.artists_string(" & "), "Daft Punk & Pharrell Williams");
+        assert_eq!(track.album.title, "Random Access Memories");
+        assert_eq!(track.album.release_date.year, 2013);
+        assert_eq!((track.track_number, track.album.total_tracks), (8, 13));
+        assert_eq!(track.disc_number, 1);
+        assert_eq!(track.ids.isrc.as_deref(), Some("USQX91300108"));
+        assert_eq!(track.deezer_id(), None);
+
+        let empty = Track::from_metadata(&AudioMetadata::new());
+        assert!(empty.artists.is_empty());
+        assert!(empty.is_orphan());
+    }
+
     #[test]
     fn test_track_duration_formatted() {
         let track = Track {
@@ -215,6 +455,16 @@ mod tests {
         assert_eq!(track.duration_formatted(), "03:35");
     }
 
+    #[test]
+    fn test_track_duration() {
+        let track = Track {
+            duration_ms: 215_500,
+            ..Default::default()
+        };
+        assert_eq!(track.duration(), Duration::from_millis(215_500));
+        assert_eq!(track.duration_secs(), 215);
+    }
+
     #[test]
     fn test_track_artists_string() {
         let track = Track {
@@ -235,4 +485,58 @@ mod tests {
         };
         assert_eq!(track.primary_artist(), Some("Main Artist"));
     }
+
+    #[test]
+    fn test_contributors_with_role() {
+        let track = Track {
+            contributors: vec![
+                Contributor::new("Daft Punk", "Main", "27"),
+                Contributor::new("Pharrell Williams", "Featured", "103"),
+                Contributor::new("Nile Rodgers", "featured", "1454"),
+            ],
+            ..Default::default()
+        };
+
+        let featured: Vec<_> = track
+            .contributors_with_role("Featured")
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(featured, vec!["Pharrell Williams", "Nile Rodgers"]);
+        assert_eq!(track.contributors_with_role("Composer").count(), 0);
+    }
+
+    #[test]
+    fn test_artists_formatted() {
+        let mut track = Track {
+            artists: vec![
+                ArtistTrack::new("Daft Punk", "27"),
+                ArtistTrack::new("Pharrell Williams", "103"),
+                ArtistTrack::new("Nile Rodgers", "1454"),
+            ],
+            ..Default::default()
+        };
+
+        // Without roles, every style joins all artists
+        assert_eq!(
+            track.artists_formatted(&ArtistStyle::MainFeat),
+            "Daft Punk, Pharrell Williams, Nile Rodgers"
+        );
+
+        track.contributors = vec![
+            Contributor::new("Daft Punk", "Main", "27"),
+            Contributor::new("Pharrell Williams", "Featured", "103"),
+            Contributor::new("Nile Rodgers", "Featured", "1454"),
+        ];
+        assert_eq!(
+            track.artists_formatted(&ArtistStyle::MainFeat),
+            "Daft Punk feat. Pharrell Williams, Nile Rodgers"
+        );
+        assert_eq!(
+            track.artists_formatted(&ArtistStyle::Join(" & ".to_string())),
+            "Daft Punk & Pharrell Williams & Nile Rodgers"
+        );
+
+        track.contributors.truncate(1);
+        assert_eq!(track.artists_formatted(&ArtistStyle::MainFeat), "Daft Punk");
+    }
 }
@@ -4,10 +4,12 @@
 //! into downloaded audio files (MP3 and FLAC).
 
 use lofty::config::WriteOptions;
-use lofty::file::TaggedFileExt;
+use lofty::file::{AudioFile, TaggedFile, TaggedFileExt};
 use lofty::picture::{MimeType, Picture, PictureType};
-use lofty::tag::{Accessor, TagExt};
+use lofty::probe::Probe;
+use lofty::tag::{Accessor, ItemKey, ItemValue, TagExt};
 use std::path::Path;
+use std::time::Duration;
 use tracing::{debug, warn};
 
 use crate::error::Result;
@@ -31,12 +33,30 @@ pub struct AudioMetadata {
     pub disc_number: Option<u32>,
     /// Total discs.
     pub total_discs: Option<u32>,
+    /// Disc subtitle (box sets).
+    pub disc_subtitle: Option<String>,
+    /// Record label.
+    pub label: Option<String>,
     /// Release year.
     pub year: Option<i32>,
     /// Genre(s).
     pub genre: Option<String>,
     /// ISRC code.
     pub isrc: Option<String>,
+    /// Whether the album is a compilation.
+    pub compilation: bool,
+    /// Content group, e.g. the playlist a track was downloaded from.
+    pub grouping: Option<String>,
+    /// Unsynced lyrics.
+    pub lyrics: Option<String>,
+    /// Composer(s).
+    pub composer: Option<String>,
+    /// Beats per minute.
+    pub bpm: Option<u32>,
+    /// Free-form comment.
+    pub comment: Option<String>,
+    /// ReplayGain track gain in dB.
+    pub replaygain_track_gain: Option<f32>,
     /// Cover art as JPEG bytes.
     pub cover_art: Option<Vec<u8>>,
 }
@@ -85,6 +105,18 @@ impl AudioMetadata {
         self
     }
 
+    /// Set disc subtitle.
+    pub fn with_disc_subtitle<S: Into<String>>(mut self, subtitle: S) -> Self {
+        self.disc_subtitle = Some(subtitle.into());
+        self
+    }
+
+    /// Set record label.
+    pub fn with_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
     /// Set year.
     pub fn with_year(mut self, year: i32) -> Self {
         self.year = Some(year);
@@ -103,6 +135,48 @@ impl AudioMetadata {
         self
     }
 
+    /// Set content group.
+    pub fn with_grouping<S: Into<String>>(mut self, grouping: S) -> Self {
+        self.grouping = Some(grouping.into());
+        self
+    }
+
+    /// Set unsynced lyrics.
+    pub fn with_lyrics<S: Into<String>>(mut self, lyrics: S) -> Self {
+        self.lyrics = Some(lyrics.into());
+        self
+    }
+
+    /// Set composer.
+    pub fn with_composer<S: Into<String>>(mut self, composer: S) -> Self {
+        self.composer = Some(composer.into());
+        self
+    }
+
+    /// Set beats per minute.
+    pub fn with_bpm(mut self, bpm: u32) -> Self {
+        self.bpm = Some(bpm);
+        self
+    }
+
+    /// Set comment.
+    pub fn with_comment<S: Into<String>>(mut self, comment: S) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Set the ReplayGain track gain in dB.
+    pub fn with_replaygain(mut self, track_gain: f32) -> Self {
+        self.replaygain_track_gain = Some(track_gain);
+        self
+    }
+
+    /// Set the compilation flag.
+    pub fn with_compilation(mut self, compilation: bool) -> Self {
+        self.compilation = compilation;
+        self
+    }
+
     /// Set cover art from JPEG bytes.
     pub fn with_cover_art(mut self, cover: Vec<u8>) -> Self {
         self.cover_art = Some(cover);
@@ -110,7 +184,17 @@ impl AudioMetadata {
     }
 }
 
-/// Write metadata to an audio file.
+/// How tags already present in a file are treated when writing metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagMergeMode {
+    /// Keep existing fields; only the fields being written are replaced.
+    #[default]
+    Merge,
+    /// Remove every existing field and picture before writing.
+    Overwrite,
+}
+
+/// Write metadata to an audio file, merging with existing tags.
 ///
 /// Supports MP3 (ID3v2.4) and FLAC (Vorbis Comments).
 ///
@@ -123,11 +207,28 @@ impl AudioMetadata {
 ///
 /// Returns an error if the file cannot be read or written.
 pub fn write_metadata<P: AsRef<Path>>(path: P, metadata: &AudioMetadata) -> Result<()> {
+    write_metadata_with_mode(path, metadata, TagMergeMode::Merge)
+}
+
+/// Write metadata to an audio file, treating existing tags as `mode` says.
+///
+/// In either mode a written field replaces any existing value of the same
+/// field rather than being added next to it, and a new cover replaces the
+/// existing front cover.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or written.
+pub fn write_metadata_with_mode<P: AsRef<Path>>(
+    path: P,
+    metadata: &AudioMetadata,
+    mode: TagMergeMode,
+) -> Result<()> {
     let path = path.as_ref();
     debug!("Writing metadata to: {}", path.display());
 
     // Read the file
-    let mut tagged_file = match lofty::read_from_path(path) {
+    let mut tagged_file = match read_audio_file(path) {
         Ok(f) => f,
         Err(e) => {
             warn!("Could not read file for tagging: {}", e);
@@ -146,6 +247,10 @@ pub fn write_metadata<P: AsRef<Path>>(path: P, metadata: &AudioMetadata) -> Resu
         }
     };
 
+    if mode == TagMergeMode::Overwrite {
+        tag.clear();
+    }
+
     // Set basic metadata
     if let Some(title) = &metadata.title {
         tag.set_title(title.clone());
@@ -159,11 +264,16 @@ pub fn write_metadata<P: AsRef<Path>>(path: P, metadata: &AudioMetadata) -> Resu
         tag.set_album(album.clone());
     }
 
+    if let Some(album_artist) = &metadata.album_artist {
+        tag.insert_text(ItemKey::AlbumArtist, album_artist.clone());
+    }
+
     if let Some(track) = metadata.track_number {
         tag.set_track(track);
     }
 
-    if let Some(total) = metadata.total_tracks {
+    // A total of 0 means unknown, and would show up as "1 of 0"
+    if let Some(total) = metadata.total_tracks.filter(|&total| total > 0) {
         tag.set_track_total(total);
     }
 
@@ -171,10 +281,60 @@ pub fn write_metadata<P: AsRef<Path>>(path: P, metadata: &AudioMetadata) -> Resu
         tag.set_disk(disc);
     }
 
-    if let Some(total) = metadata.total_discs {
+    if let Some(total) = metadata.total_discs.filter(|&total| total > 0) {
         tag.set_disk_total(total);
     }
 
+    // TSST in ID3, DISCSUBTITLE in Vorbis comments
+    if let Some(subtitle) = &metadata.disc_subtitle {
+        tag.insert_text(ItemKey::SetSubtitle, subtitle.clone());
+    }
+
+    // TPUB in ID3, LABEL in Vorbis comments
+    if let Some(label) = &metadata.label {
+        tag.insert_text(ItemKey::Label, label.clone());
+    }
+
+    // TIT1 in ID3, GROUPING in Vorbis comments
+    if let Some(grouping) = &metadata.grouping {
+        tag.insert_text(ItemKey::ContentGroup, grouping.clone());
+    }
+
+    // USLT in ID3, LYRICS in Vorbis comments
+    if let Some(lyrics) = &metadata.lyrics {
+        tag.insert_text(ItemKey::Lyrics, lyrics.clone());
+    }
+
+    // TCOM in ID3, COMPOSER in Vorbis comments
+    if let Some(composer) = &metadata.composer {
+        tag.insert_text(ItemKey::Composer, composer.clone());
+    }
+
+    // TBPM in ID3, BPM in Vorbis comments. Each format only maps one of
+    // the two keys, so the other insert is a no-op.
+    if let Some(bpm) = metadata.bpm.filter(|&bpm| bpm > 0) {
+        tag.insert_text(ItemKey::IntegerBpm, bpm.to_string());
+        tag.insert_text(ItemKey::Bpm, bpm.to_string());
+    }
+
+    // COMM in ID3, COMMENT in Vorbis comments
+    if let Some(comment) = &metadata.comment {
+        tag.insert_text(ItemKey::Comment, comment.clone());
+    }
+
+    // TXXX:REPLAYGAIN_TRACK_GAIN in ID3, REPLAYGAIN_TRACK_GAIN in Vorbis comments
+    if let Some(gain) = metadata
+        .replaygain_track_gain
+        .filter(|gain| gain.is_finite())
+    {
+        tag.insert_text(ItemKey::ReplayGainTrackGain, format!("{:.2} dB", gain));
+    }
+
+    // TCMP in ID3, COMPILATION in Vorbis comments
+    if metadata.compilation {
+        tag.insert_text(ItemKey::FlagCompilation, "1".to_string());
+    }
+
     if let Some(year) = metadata.year {
         if year > 0 {
             tag.set_year(year as u32);
@@ -203,6 +363,7 @@ pub fn write_metadata<P: AsRef<Path>>(path: P, metadata: &AudioMetadata) -> Resu
             cover_data.clone(),
         );
 
+        tag.remove_picture_type(PictureType::CoverFront);
         tag.push_picture(picture);
     }
 
@@ -217,6 +378,77 @@ pub fn write_metadata<P: AsRef<Path>>(path: P, metadata: &AudioMetadata) -> Resu
     Ok(())
 }
 
+/// Read an audio file, detecting its format from its contents.
+///
+/// Downloads are tagged while they still have a `.part` extension, so the
+/// extension can't be relied on.
+fn read_audio_file(path: &Path) -> lofty::error::Result<TaggedFile> {
+    Probe::open(path)?.guess_file_type()?.read()
+}
+
+/// Read the audio duration of a file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a supported audio file.
+pub fn read_duration<P: AsRef<Path>>(path: P) -> Result<Duration> {
+    let tagged_file = read_audio_file(path.as_ref())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(tagged_file.properties().duration())
+}
+
+/// Read every tag item from an audio file as key/value pairs.
+///
+/// Unlike [`write_metadata`], which only deals with the fields modeled by
+/// [`AudioMetadata`], this returns everything lofty can read, including
+/// comments and custom frames. Keys are the format's native names where
+/// known (e.g. `TIT2` in ID3v2, `TITLE` in Vorbis comments). Binary values
+/// and embedded pictures are summarized rather than returned raw.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a supported audio file.
+pub fn read_all_tags<P: AsRef<Path>>(path: P) -> Result<Vec<(String, String)>> {
+    let tagged_file = read_audio_file(path.as_ref())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    let mut tags = Vec::new();
+    for tag in tagged_file.tags() {
+        for item in tag.items() {
+            let key = match item.key() {
+                ItemKey::Unknown(key) => key.clone(),
+                key => key
+                    .map_key(tag.tag_type(), true)
+                    .map(|k| k.to_string())
+                    .unwrap_or_else(|| format!("{:?}", key)),
+            };
+            let value = match item.value() {
+                ItemValue::Text(text) | ItemValue::Locator(text) => text.clone(),
+                ItemValue::Binary(data) => format!("<{} bytes>", data.len()),
+            };
+            tags.push((key, value));
+        }
+
+        for picture in tag.pictures() {
+            let mime = picture
+                .mime_type()
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            tags.push((
+                "PICTURE".to_string(),
+                format!(
+                    "{:?}, {}, {} bytes",
+                    picture.pic_type(),
+                    mime,
+                    picture.data().len()
+                ),
+            ));
+        }
+    }
+
+    Ok(tags)
+}
+
 /// Fetch cover art from Deezer.
 pub async fn fetch_cover_art(cover_url: &str) -> Option<Vec<u8>> {
     if cover_url.is_empty() {
@@ -267,4 +499,173 @@ mod tests {
         assert_eq!(meta.total_tracks, Some(10));
         assert_eq!(meta.year, Some(2024));
     }
+
+    /// Minimal FLAC: signature, STREAMINFO and PADDING blocks.
+    fn minimal_flac() -> Vec<u8> {
+        let mut flac = b"fLaC".to_vec();
+        flac.extend_from_slice(&[0x00, 0x00, 0x00, 0x22]);
+        let mut stream_info = [0u8; 34];
+        stream_info[0..2].copy_from_slice(&4096u16.to_be_bytes());
+        stream_info[2..4].copy_from_slice(&4096u16.to_be_bytes());
+        // 44100 Hz, 2 channels, 16 bits per sample
+        stream_info[10..14].copy_from_slice(&[0x0A, 0xC4, 0x42, 0xF0]);
+        flac.extend_from_slice(&stream_info);
+        flac.extend_from_slice(&[0x81, 0x00, 0x00, 0x08]);
+        flac.extend_from_slice(&[0u8; 8]);
+        flac.extend_from_slice(&[0xFF, 0xF8, 0x00, 0x00]);
+        flac
+    }
+
+    /// Write `metadata` to a fresh minimal FLAC named `name` and read back
+    /// every tag.
+    fn write_and_read_tags(name: &str, metadata: &AudioMetadata) -> Vec<(String, String)> {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, minimal_flac()).unwrap();
+        write_metadata(&path, metadata).unwrap();
+        let tags = read_all_tags(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        tags
+    }
+
+    /// Check whether `tags` contains `key` with `value`.
+    fn has_tag(tags: &[(String, String)], key: &str, value: &str) -> bool {
+        tags.iter().any(|(k, v)| k == key && v == value)
+    }
+
+    #[test]
+    fn test_write_metadata_skips_zero_totals() {
+        use lofty::file::TaggedFileExt;
+
+        let path = std::env::temp_dir().join("rusteer_test_zero_totals.flac");
+        std::fs::write(&path, minimal_flac()).unwrap();
+
+        let meta = AudioMetadata::new()
+            .with_track(3, Some(0))
+            .with_disc(1, Some(0));
+        write_metadata(&path, &meta).unwrap();
+
+        let tagged = lofty::read_from_path(&path).unwrap();
+        let tag = tagged.primary_tag().unwrap();
+        assert_eq!(tag.track(), Some(3));
+        assert_eq!(tag.track_total(), None);
+        assert_eq!(tag.disk(), Some(1));
+        assert_eq!(tag.disk_total(), None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_write_metadata_to_part_file() {
+        // Downloads are tagged before losing their `.part` extension
+        let meta = AudioMetadata::new().with_title("One More Time");
+        let tags = write_and_read_tags("rusteer_test_part.flac.part", &meta);
+        assert!(has_tag(&tags, "TITLE", "One More Time"));
+    }
+
+    #[test]
+    fn test_write_disc_subtitle() {
+        let meta = AudioMetadata::new().with_disc_subtitle("The Studio Album");
+        let tags = write_and_read_tags("rusteer_test_disc_subtitle.flac", &meta);
+        assert!(has_tag(&tags, "DISCSUBTITLE", "The Studio Album"));
+    }
+
+    #[test]
+    fn test_write_label() {
+        let meta = AudioMetadata::new().with_label("Parlophone");
+        let tags = write_and_read_tags("rusteer_test_label.flac", &meta);
+        assert!(has_tag(&tags, "LABEL", "Parlophone"));
+    }
+
+    #[test]
+    fn test_write_compilation() {
+        let meta = AudioMetadata::new()
+            .with_album_artist("Various Artists")
+            .with_compilation(true);
+        let tags = write_and_read_tags("rusteer_test_compilation.flac", &meta);
+        assert!(has_tag(&tags, "ALBUMARTIST", "Various Artists"));
+        assert!(has_tag(&tags, "COMPILATION", "1"));
+
+        let meta = AudioMetadata::new().with_compilation(false);
+        let tags = write_and_read_tags("rusteer_test_not_compilation.flac", &meta);
+        assert!(!tags.iter().any(|(key, _)| key == "COMPILATION"));
+    }
+
+    #[test]
+    fn test_write_grouping() {
+        let meta = AudioMetadata::new().with_grouping("Release Radar");
+        let tags = write_and_read_tags("rusteer_test_grouping.flac", &meta);
+        assert!(has_tag(&tags, "GROUPING", "Release Radar"));
+    }
+
+    #[test]
+    fn test_write_lyrics() {
+        let meta = AudioMetadata::new().with_lyrics("Line one\nLine two");
+        let tags = write_and_read_tags("rusteer_test_lyrics.flac", &meta);
+        assert!(has_tag(&tags, "LYRICS", "Line one\nLine two"));
+    }
+
+    #[test]
+    fn test_write_composer_bpm_comment() {
+        let meta = AudioMetadata::new()
+            .with_composer("Thom Yorke")
+            .with_bpm(124)
+            .with_comment("Ripped with care");
+        let tags = write_and_read_tags("rusteer_test_composer.flac", &meta);
+        assert!(has_tag(&tags, "COMPOSER", "Thom Yorke"));
+        assert!(has_tag(&tags, "BPM", "124"));
+        assert!(has_tag(&tags, "COMMENT", "Ripped with care"));
+    }
+
+    #[test]
+    fn test_write_replaygain() {
+        let meta = AudioMetadata::new().with_replaygain(-6.544);
+        let tags = write_and_read_tags("rusteer_test_replaygain.flac", &meta);
+        assert!(has_tag(&tags, "REPLAYGAIN_TRACK_GAIN", "-6.54 dB"));
+    }
+
+    #[test]
+    fn test_tag_merge_modes() {
+        use lofty::file::TaggedFileExt;
+
+        let path = std::env::temp_dir().join("rusteer_test_merge_mode.flac");
+        std::fs::write(&path, minimal_flac()).unwrap();
+
+        let cover = vec![0xFF, 0xD8, 0xFF, 0xE0];
+        let first = AudioMetadata::new()
+            .with_title("Original")
+            .with_cover_art(cover.clone());
+        write_metadata(&path, &first).unwrap();
+
+        let second = AudioMetadata::new()
+            .with_artist("Artist")
+            .with_cover_art(cover);
+        write_metadata_with_mode(&path, &second, TagMergeMode::Merge).unwrap();
+
+        let tagged = lofty::read_from_path(&path).unwrap();
+        let tag = tagged.primary_tag().unwrap();
+        assert_eq!(tag.title().as_deref(), Some("Original"));
+        assert_eq!(tag.artist().as_deref(), Some("Artist"));
+        assert_eq!(tag.pictures().len(), 1);
+
+        let third = AudioMetadata::new().with_album("Album");
+        write_metadata_with_mode(&path, &third, TagMergeMode::Overwrite).unwrap();
+
+        let tagged = lofty::read_from_path(&path).unwrap();
+        let tag = tagged.primary_tag().unwrap();
+        assert_eq!(tag.title(), None);
+        assert_eq!(tag.album().as_deref(), Some("Album"));
+        assert!(tag.pictures().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_read_all_tags_rejects_non_audio() {
+        let path = std::env::temp_dir().join("rusteer_test_not_audio.txt");
+        std::fs::write(&path, b"not audio").unwrap();
+
+        assert!(read_all_tags(&path).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -0,0 +1,89 @@
+//! Token-bucket throttling.
+//!
+//! Used to cap throughput across every download sharing the same limit.
+
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// A token bucket that can be shared between concurrent tasks.
+///
+/// Tokens refill continuously at `rate` per second, up to `capacity`.
+/// Callers that take more tokens than are available go into debt and
+/// sleep until the debt is paid off, so the aggregate rate stays bounded
+/// no matter how many tasks share the bucket.
+#[derive(Debug)]
+pub(crate) struct Throttle {
+    rate: f64,
+    capacity: f64,
+    state: Mutex<ThrottleState>,
+}
+
+#[derive(Debug)]
+struct ThrottleState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Throttle {
+    /// Create a bucket refilling at `rate` tokens per second.
+    ///
+    /// The bucket starts full and holds at most `capacity` tokens.
+    pub(crate) fn new(rate: f64, capacity: f64) -> Self {
+        Self {
+            rate,
+            capacity,
+            state: Mutex::new(ThrottleState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Get the refill rate in tokens per second.
+    pub(crate) fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Take `amount` tokens, sleeping if the bucket is in debt.
+    pub(crate) async fn acquire(&self, amount: f64) {
+        let wait = {
+            let mut state = self.state.lock().await;
+
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * self.rate).min(self.capacity);
+            state.last_refill = now;
+
+            state.tokens -= amount;
+            if state.tokens >= 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(-state.tokens / self.rate)
+            }
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_throttle_waits_when_empty() {
+        let throttle = Throttle::new(10_000.0, 10_000.0);
+
+        // The initial burst is free
+        let start = Instant::now();
+        throttle.acquire(10_000.0).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // The next 1000 tokens take ~100ms to refill
+        let start = Instant::now();
+        throttle.acquire(1_000.0).await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}